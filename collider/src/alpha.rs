@@ -0,0 +1,455 @@
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+
+/// Pixels with an alpha above this value are opaque, unless a `PixelPerfect` sprite sets its own.
+/// Half transparent edges from anti-aliasing are not solid.
+pub const DEFAULT_ALPHA_THRESHOLD: u8 = 127;
+
+/// Represents which pixels of an image are opaque.
+#[derive(Clone, Debug)]
+pub struct AlphaMask {
+    pub width: u32,
+    pub height: u32,
+    opaque: Vec<bool>,
+}
+
+impl AlphaMask {
+    /// Creates a mask from an image, or None if its format has no 8 bit alpha channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: The image of the sprite.
+    /// * `threshold`: Pixels with an alpha above this value are opaque.
+    pub fn from_image(image: &Image, threshold: u8) -> Option<Self> {
+        let alpha_index = match image.texture_descriptor.format {
+            TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb => 3,
+            _ => return None,
+        };
+
+        let width = image.texture_descriptor.size.width;
+        let height = image.texture_descriptor.size.height;
+        let opaque = image
+            .data
+            .chunks_exact(4)
+            .take((width * height) as usize)
+            .map(|pixel| pixel[alpha_index] > threshold)
+            .collect();
+
+        Some(Self {
+            width,
+            height,
+            opaque,
+        })
+    }
+
+    /// Returns true if the pixel at (x, y) is opaque, counting rows from the top.
+    pub fn is_opaque(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return false;
+        }
+        self.opaque[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    /// Gets the smallest box containing every opaque pixel, as (min, max) pixels.
+    pub fn bounds(&self) -> Option<(UVec2, UVec2)> {
+        let mut bounds: Option<(UVec2, UVec2)> = None;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_opaque(x as i32, y as i32) {
+                    let pixel = UVec2::new(x, y);
+                    bounds = Some(match bounds {
+                        Some((min, max)) => (min.min(pixel), max.max(pixel)),
+                        None => (pixel, pixel),
+                    });
+                }
+            }
+        }
+        bounds
+    }
+}
+
+/// Marks a sprite that keeps its alpha mask for pixel-perfect tests.
+#[derive(Clone, Component, Debug)]
+pub struct PixelPerfect {
+    /// Pixels with an alpha above this value are opaque.
+    pub alpha_threshold: u8,
+}
+
+impl Default for PixelPerfect {
+    fn default() -> Self {
+        Self {
+            alpha_threshold: DEFAULT_ALPHA_THRESHOLD,
+        }
+    }
+}
+
+/// Represents the opaque part of a sprite, so transparent padding is not solid.
+#[derive(Clone, Component, Debug)]
+pub struct SpriteCollider {
+    /// Center of the opaque box, relative to the center of the sprite when not flipped.
+    pub offset: Vec2,
+    pub width: f32,
+    pub height: f32,
+    /// Opaque pixels of the whole image, only kept for `PixelPerfect` sprites.
+    pub mask: Option<AlphaMask>,
+}
+
+impl SpriteCollider {
+    /// Creates a collider from the opaque pixels of an image.
+    /// Images without alpha use their full size, fully transparent images have no size.
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: The image of the sprite.
+    /// * `threshold`: Pixels with an alpha above this value are opaque.
+    /// * `keep_mask`: Whether to keep the mask for pixel-perfect tests.
+    pub fn from_image(image: &Image, threshold: u8, keep_mask: bool) -> Self {
+        let size = image.texture_descriptor.size;
+        let (image_width, image_height) = (size.width as f32, size.height as f32);
+
+        let mask = match AlphaMask::from_image(image, threshold) {
+            Some(mask) => mask,
+            None => {
+                return Self {
+                    offset: Vec2::ZERO,
+                    width: image_width,
+                    height: image_height,
+                    mask: None,
+                }
+            }
+        };
+
+        let (offset, width, height) = match mask.bounds() {
+            Some((min, max)) => {
+                let (min, max) = (min.as_vec2(), max.as_vec2() + Vec2::ONE);
+                let center = (min + max) / 2.0;
+                let offset = Vec2::new(center.x - image_width / 2.0, image_height / 2.0 - center.y);
+                (offset, max.x - min.x, max.y - min.y)
+            }
+            None => (Vec2::ZERO, 0.0, 0.0),
+        };
+
+        Self {
+            offset,
+            width,
+            height,
+            mask: if keep_mask { Some(mask) } else { None },
+        }
+    }
+
+    /// Gets the center of the opaque box for a sprite at a position.
+    pub fn center(&self, position: Vec3, sprite: &Sprite) -> Vec3 {
+        let mut offset = self.offset;
+        if sprite.flip_x {
+            offset.x = -offset.x;
+        }
+        if sprite.flip_y {
+            offset.y = -offset.y;
+        }
+        position + offset.extend(0.0)
+    }
+
+    /// Transforms a world point into the unflipped image of a sprite,
+    /// relative to its center with y pointing up.
+    fn to_local(transform: &Transform, sprite: &Sprite, point: Vec2) -> Vec2 {
+        let mut local = transform
+            .compute_matrix()
+            .inverse()
+            .transform_point3(point.extend(transform.translation.z))
+            .truncate();
+        if sprite.flip_x {
+            local.x = -local.x;
+        }
+        if sprite.flip_y {
+            local.y = -local.y;
+        }
+        local
+    }
+
+    /// Gets the corners of the opaque box of a sprite in world space.
+    fn corners(&self, transform: &Transform, sprite: &Sprite) -> [Vec2; 4] {
+        let center = self.center(Vec3::ZERO, sprite).truncate();
+        let half = Vec2::new(self.width, self.height) / 2.0;
+        let matrix = transform.compute_matrix();
+        [
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ]
+        .map(|corner| {
+            matrix
+                .transform_point3((center + corner).extend(0.0))
+                .truncate()
+        })
+    }
+
+    /// Returns true if the world point is on an opaque pixel of a sprite with a transform.
+    /// The point is moved into the space of the image, so rotation and scale are respected.
+    /// Without a mask the whole opaque box counts.
+    pub fn is_opaque_at(&self, transform: &Transform, sprite: &Sprite, point: Vec2) -> bool {
+        let local = Self::to_local(transform, sprite, point);
+        let mask = match &self.mask {
+            Some(mask) => mask,
+            None => {
+                return (local.x - self.offset.x).abs() <= self.width / 2.0
+                    && (local.y - self.offset.y).abs() <= self.height / 2.0;
+            }
+        };
+
+        let x = (local.x + mask.width as f32 / 2.0).floor() as i32;
+        let y = (mask.height as f32 / 2.0 - local.y).floor() as i32;
+        mask.is_opaque(x, y)
+    }
+}
+
+/// Gets the box used for collisions of a sprite, as (center, width, height).
+/// Sprites without a collider use the full size of their image.
+pub fn sprite_bounds(
+    position: Vec3,
+    sprite: &Sprite,
+    image: &Image,
+    collider: Option<&SpriteCollider>,
+) -> (Vec3, f32, f32) {
+    match collider {
+        Some(collider) => (
+            collider.center(position, sprite),
+            collider.width,
+            collider.height,
+        ),
+        None => {
+            let size = image.texture_descriptor.size;
+            (position, size.width as f32, size.height as f32)
+        }
+    }
+}
+
+/// Returns true if the opaque parts of 2 sprites overlap.
+/// Pixels are compared when both sprites have a mask, otherwise the world-space
+/// bounds of their opaque boxes are.
+pub fn collide_sprite_sprite(
+    transform_a: &Transform,
+    sprite_a: &Sprite,
+    a: &SpriteCollider,
+    transform_b: &Transform,
+    sprite_b: &Sprite,
+    b: &SpriteCollider,
+) -> bool {
+    let bounds = |corners: [Vec2; 4]| {
+        corners.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), corner| (min.min(*corner), max.max(*corner)),
+        )
+    };
+    let (min_a, max_a) = bounds(a.corners(transform_a, sprite_a));
+    let (min_b, max_b) = bounds(b.corners(transform_b, sprite_b));
+
+    // Overlap of the opaque boxes
+    let min = min_a.max(min_b);
+    let max = max_a.min(max_b);
+    if min.x >= max.x || min.y >= max.y {
+        return false;
+    }
+    if a.mask.is_none() || b.mask.is_none() {
+        return true;
+    }
+
+    // Sample both masks at every pixel center of the overlap
+    let (x0, x1) = (min.x.floor() as i32, max.x.ceil() as i32);
+    let (y0, y1) = (min.y.floor() as i32, max.y.ceil() as i32);
+    (y0..y1).any(|y| {
+        (x0..x1).any(|x| {
+            let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            a.is_opaque_at(transform_a, sprite_a, point)
+                && b.is_opaque_at(transform_b, sprite_b, point)
+        })
+    })
+}
+
+/// Plugin that builds sprite colliders from the alpha of their images.
+pub struct AlphaPlugin;
+
+impl Plugin for AlphaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(sprite_collider_system);
+    }
+}
+
+/// System used for building colliders once images are loaded,
+/// and rebuilding them when the image or the alpha threshold changes.
+fn sprite_collider_system(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    sprites: Query<
+        (
+            Entity,
+            &Handle<Image>,
+            Option<&SpriteCollider>,
+            Option<&PixelPerfect>,
+            Option<ChangeTrackers<PixelPerfect>>,
+        ),
+        With<Sprite>,
+    >,
+) {
+    let modified: Vec<&Handle<Image>> = image_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, texture, collider, pixel_perfect, pixel_perfect_tracker) in sprites.iter() {
+        let pixel_perfect_changed =
+            pixel_perfect_tracker.map_or(false, |tracker| tracker.is_changed());
+        if collider.is_some() && !modified.contains(&texture) && !pixel_perfect_changed {
+            continue;
+        }
+        if let Some(image) = images.get(texture) {
+            let threshold = pixel_perfect.map_or(DEFAULT_ALPHA_THRESHOLD, |pixel_perfect| {
+                pixel_perfect.alpha_threshold
+            });
+            let collider = SpriteCollider::from_image(image, threshold, pixel_perfect.is_some());
+            commands.entity(entity).insert(collider);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+    use std::f32::consts::FRAC_PI_2;
+
+    /// Creates an RGBA image from the alpha of its pixels, by rows from the top.
+    fn image(width: u32, height: u32, alpha: &[u8]) -> Image {
+        let data = alpha.iter().flat_map(|a| [255, 255, 255, *a]).collect();
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    #[test]
+    fn mask_threshold() {
+        let image = image(3, 1, &[0, 100, 200]);
+
+        let mask = AlphaMask::from_image(&image, DEFAULT_ALPHA_THRESHOLD).unwrap();
+        assert!(!mask.is_opaque(0, 0));
+        assert!(!mask.is_opaque(1, 0));
+        assert!(mask.is_opaque(2, 0));
+
+        let mask = AlphaMask::from_image(&image, 0).unwrap();
+        assert!(!mask.is_opaque(0, 0));
+        assert!(mask.is_opaque(1, 0));
+        assert!(mask.is_opaque(2, 0));
+
+        // Outside of the image
+        assert!(!mask.is_opaque(-1, 0));
+        assert!(!mask.is_opaque(3, 0));
+        assert!(!mask.is_opaque(0, 1));
+    }
+
+    #[test]
+    fn mask_bounds() {
+        #[rustfmt::skip]
+        let image = image(4, 4, &[
+            0, 0,   0,   0,
+            0, 255, 0,   0,
+            0, 0,   0,   0,
+            0, 0,   255, 0,
+        ]);
+        let mask = AlphaMask::from_image(&image, DEFAULT_ALPHA_THRESHOLD).unwrap();
+        assert_eq!(mask.bounds(), Some((UVec2::new(1, 1), UVec2::new(2, 3))));
+
+        let empty = AlphaMask::from_image(&self::image(2, 2, &[0; 4]), 0).unwrap();
+        assert_eq!(empty.bounds(), None);
+    }
+
+    #[test]
+    fn collider_offset() {
+        // Opaque 2x2 block in the top right corner
+        #[rustfmt::skip]
+        let image = image(4, 4, &[
+            0, 0, 255, 255,
+            0, 0, 255, 255,
+            0, 0, 0,   0,
+            0, 0, 0,   0,
+        ]);
+        let collider = SpriteCollider::from_image(&image, DEFAULT_ALPHA_THRESHOLD, false);
+        assert_eq!(collider.offset, Vec2::new(1.0, 1.0));
+        assert_eq!((collider.width, collider.height), (2.0, 2.0));
+        assert!(collider.mask.is_none());
+
+        let flipped = Sprite {
+            flip_x: true,
+            flip_y: true,
+            ..Default::default()
+        };
+        let center = collider.center(Vec3::new(10.0, 10.0, 0.0), &flipped);
+        assert_eq!(center, Vec3::new(9.0, 9.0, 0.0));
+    }
+
+    #[test]
+    fn opaque_at_transform() {
+        // Left half of a 4x2 image is opaque
+        let image = image(4, 2, &[255, 255, 0, 0, 255, 255, 0, 0]);
+        let collider = SpriteCollider::from_image(&image, DEFAULT_ALPHA_THRESHOLD, true);
+        let sprite = Sprite::default();
+
+        let transform = Transform::default();
+        assert!(collider.is_opaque_at(&transform, &sprite, Vec2::new(-1.5, 0.5)));
+        assert!(!collider.is_opaque_at(&transform, &sprite, Vec2::new(1.5, 0.5)));
+
+        // Rotated a quarter turn, the left half points down
+        let transform = Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_2));
+        assert!(collider.is_opaque_at(&transform, &sprite, Vec2::new(0.5, -1.5)));
+        assert!(!collider.is_opaque_at(&transform, &sprite, Vec2::new(0.5, 1.5)));
+        assert!(!collider.is_opaque_at(&transform, &sprite, Vec2::new(-1.5, 0.5)));
+
+        // Scaled twice as large
+        let transform = Transform::from_scale(Vec3::splat(2.0));
+        assert!(collider.is_opaque_at(&transform, &sprite, Vec2::new(-3.5, 1.0)));
+        assert!(!collider.is_opaque_at(&transform, &sprite, Vec2::new(-4.5, 1.0)));
+
+        // Flipped and moved
+        let transform = Transform::from_xyz(10.0, 0.0, 0.0);
+        let flipped = Sprite {
+            flip_x: true,
+            ..Default::default()
+        };
+        assert!(collider.is_opaque_at(&transform, &flipped, Vec2::new(11.5, 0.5)));
+        assert!(!collider.is_opaque_at(&transform, &flipped, Vec2::new(8.5, 0.5)));
+    }
+
+    #[test]
+    fn sprites_overlap_rotated() {
+        // 4x2 images whose left half is opaque
+        let image = image(4, 2, &[255, 255, 0, 0, 255, 255, 0, 0]);
+        let collider = SpriteCollider::from_image(&image, DEFAULT_ALPHA_THRESHOLD, true);
+        let sprite = Sprite::default();
+        let a = Transform::default();
+
+        // The transparent halves overlap, the opaque ones don't
+        let b = Transform::from_xyz(3.0, 0.0, 0.0);
+        assert!(!collide_sprite_sprite(
+            &a, &sprite, &collider, &b, &sprite, &collider
+        ));
+
+        // Turned around, the opaque halves overlap
+        let b = Transform::from_xyz(-3.0, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::PI));
+        assert!(collide_sprite_sprite(
+            &a, &sprite, &collider, &b, &sprite, &collider
+        ));
+    }
+}
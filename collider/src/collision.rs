@@ -1,54 +1,267 @@
 use bevy::prelude::*;
-use std::collections::HashMap;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    ops::Deref,
+};
 
-use super::{foothold::*, render::*};
+use geometry::{separate_quad_line, Contact as QuadContact, Line2d, Quad2d};
+
+use super::{alpha::*, foothold::*};
+
+// Segment queries, collision groups and the physics step are shared with the other crates
+pub use geometry::{
+    collide_segment_point, collide_segment_segment, distance::*, Collision, SegmentIntersection,
+    EPSILON, FLOOR_NORMAL_Y, POINT_ON_SEGMENT_TOLERANCE,
+};
+pub use shared::groups::*;
+pub use shared::step::*;
+
+/// Label of the system that detects collisions, for ordering systems that react to them.
+pub const COLLISION_LABEL: &str = "collision";
+
+/// Label of the system that moves sprites by their velocity, before collisions are detected.
+pub const MOVEMENT_LABEL: &str = "movement";
+
+/// Represents how fast a sprite moves, in pixels per second.
+/// Set it before the `PhysicsStage`, which moves the sprite every physics step.
+#[derive(Clone, Component, Debug, Default)]
+pub struct Velocity(pub Vec2);
+
+/// Event sent when a sprite starts touching a foothold.
+#[derive(Debug, Clone)]
+pub struct CollisionStarted {
+    pub sprite: Entity,
+    pub foothold: Entity,
+    pub contact: Contact,
+}
+
+/// Event sent when a sprite stops touching a foothold.
+#[derive(Debug, Clone)]
+pub struct CollisionEnded {
+    pub sprite: Entity,
+    pub foothold: Entity,
+}
+
+/// Represents the footholds a sprite is touching, updated every frame.
+#[derive(Clone, Component, Debug, Default)]
+pub struct CollisionState {
+    /// Contacts by the foothold entity that is touched.
+    pub contacts: HashMap<Entity, Contact>,
+}
+
+impl CollisionState {
+    /// Iterates over the foothold entities that are touched.
+    pub fn footholds(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.contacts.keys().copied()
+    }
+
+    /// Gets every side of the sprite that touches a foothold.
+    pub fn sides(&self) -> HashSet<CollisionType> {
+        self.contacts
+            .values()
+            .flat_map(|contact| contact.sides())
+            .collect()
+    }
+
+    /// Returns true if a side of the sprite touches a foothold.
+    pub fn is_touching(&self, side: CollisionType) -> bool {
+        self.contacts
+            .values()
+            .any(|contact| contact.points.contains_key(&side))
+    }
+
+    /// Returns true if the sprite is standing on a foothold.
+    pub fn is_grounded(&self) -> bool {
+        self.contacts.values().any(|contact| contact.is_floor())
+    }
+}
 
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_system(collision_system);
+        app.add_plugin(FixedStepPlugin::<Transform>::default())
+            .add_event::<CollisionStarted>()
+            .add_event::<CollisionEnded>()
+            .add_system_to_stage(
+                PhysicsStage,
+                velocity_clip_system
+                    .after(PREVIOUS_STEP_LABEL)
+                    .before(MOVEMENT_LABEL),
+            )
+            .add_system_to_stage(
+                PhysicsStage,
+                velocity_system
+                    .label(MOVEMENT_LABEL)
+                    .before(COLLISION_LABEL),
+            )
+            .add_system_to_stage(PhysicsStage, collision_system.label(COLLISION_LABEL))
+            .add_system_to_stage(
+                PhysicsStage,
+                collision_response_system.after(COLLISION_LABEL),
+            );
     }
 }
 
 fn collision_system(
+    mut commands: Commands,
     images: Res<Assets<Image>>,
-    sprites: Query<(&Transform, &Handle<Image>), (With<Transform>, With<Sprite>)>,
-    mut footholds: Query<(&Foothold, &mut RenderColor), (With<Foothold>, With<RenderColor>)>,
+    mut started: EventWriter<CollisionStarted>,
+    mut ended: EventWriter<CollisionEnded>,
+    mut sprites: Query<
+        (
+            Entity,
+            &Transform,
+            &Sprite,
+            &Handle<Image>,
+            Option<&SpriteCollider>,
+            Option<&CollisionGroups>,
+            Option<&mut CollisionState>,
+        ),
+        (With<Transform>, With<Sprite>),
+    >,
+    footholds: Query<(Entity, &Foothold, Option<&CollisionGroups>), With<Foothold>>,
+) {
+    for (sprite, transform, data, texture, collider, groups, state) in sprites.iter_mut() {
+        let image = match images.get(texture) {
+            Some(image) => image,
+            None => continue,
+        };
+        let (position, width, height) = sprite_bounds(transform.translation, data, image, collider);
+
+        // Only footholds the sprite's groups interact with are solid
+        let groups = groups.copied().unwrap_or_default();
+
+        let mut contacts = HashMap::new();
+        for (foothold, data, foothold_groups) in footholds.iter() {
+            let foothold_groups = foothold_groups
+                .copied()
+                .unwrap_or_else(CollisionGroups::foothold);
+            if !groups.interacts_with(&foothold_groups) {
+                continue;
+            }
+            if let Some(contact) = collide_sprite_foothold(&position, width, height, data) {
+                contacts.insert(foothold, contact);
+            }
+        }
+
+        let previous = state.as_ref().map(|state| &state.contacts);
+        for (foothold, contact) in contacts.iter() {
+            if previous.map_or(true, |previous| !previous.contains_key(foothold)) {
+                started.send(CollisionStarted {
+                    sprite,
+                    foothold: *foothold,
+                    contact: contact.clone(),
+                });
+            }
+        }
+        for foothold in previous.into_iter().flat_map(|previous| previous.keys()) {
+            if !contacts.contains_key(foothold) {
+                ended.send(CollisionEnded {
+                    sprite,
+                    foothold: *foothold,
+                });
+            }
+        }
+
+        match state {
+            Some(mut state) => state.contacts = contacts,
+            None => {
+                commands.entity(sprite).insert(CollisionState { contacts });
+            }
+        }
+    }
+}
+
+/// Move sprites by their velocity over one physics step.
+fn velocity_system(mut sprites: Query<(&mut Transform, &Velocity), With<Transform>>) {
+    let dt = PHYSICS_TIMESTEP as f32;
+    for (mut transform, velocity) in sprites.iter_mut() {
+        if velocity.0 != Vec2::ZERO {
+            transform.translation += (velocity.0 * dt).extend(0.0);
+        }
+    }
+}
+
+/// Remove the part of the velocity moving sprites into the footholds they touch.
+/// Runs before sprites move in every step, so they slide along the geometry
+/// instead of moving into it and being pushed back out.
+fn velocity_clip_system(
+    mut sprites: Query<(&mut Velocity, &CollisionState), (With<Velocity>, With<CollisionState>)>,
 ) {
-    for (transform, texture) in sprites.iter() {
-        let position = transform.translation;
-        if let Some(image) = images.get(texture) {
-            let width = image.texture_descriptor.size.width as f32;
-            let height = image.texture_descriptor.size.height as f32;
-
-            for (foothold, mut render_color) in footholds.iter_mut() {
-                let collisions = collide_sprite_foothold(&position, width, height, foothold);
-                if !collisions.is_empty() {
-                    render_color.color = Color::RED;
-                } else {
-                    render_color.color = Color::WHITE;
-                }
+    for (mut velocity, state) in sprites.iter_mut() {
+        for normal in state
+            .contacts
+            .values()
+            .filter_map(|contact| contact.response_normal())
+        {
+            let into_surface = velocity.0.dot(normal);
+            if into_surface < 0.0 {
+                velocity.0 -= normal * into_surface;
             }
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-pub enum CollisionType {
-    Top,
-    Bottom,
-    Left,
-    Right,
+/// Push sprites out of the footholds they touch, through the sides that touch them.
+fn collision_response_system(
+    mut sprites: Query<(&mut Transform, &CollisionState), (With<Transform>, With<CollisionState>)>,
+) {
+    for (mut transform, state) in sprites.iter_mut() {
+        // Resolve the deepest contacts first
+        let mut contacts: Vec<&Contact> = state.contacts.values().collect();
+        contacts.sort_by(|a, b| b.depth.partial_cmp(&a.depth).unwrap_or(Ordering::Equal));
+
+        let mut push = Vec2::ZERO;
+        for contact in contacts {
+            let normal = match contact.response_normal() {
+                Some(normal) => normal,
+                None => continue,
+            };
+
+            // Part of the depth may already be resolved by an earlier push
+            let remaining = contact.depth - push.dot(normal);
+            if remaining > 0.0 {
+                push += normal * remaining;
+            }
+        }
+
+        if push != Vec2::ZERO {
+            transform.translation += push.extend(0.0);
+        }
+    }
+}
+
+/// Represents the side of a sprite touching a foothold.
+pub type CollisionType = Collision;
+
+/// Represents a contact between a sprite and a foothold.
+///
+/// The points are by the side of the sprite they are on, and the normal points
+/// from the foothold to the sprite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    /// Id of the foothold that is touched.
+    pub foothold_id: u32,
+    pub contact: QuadContact,
+}
+
+impl Deref for Contact {
+    type Target = QuadContact;
+
+    fn deref(&self) -> &Self::Target {
+        &self.contact
+    }
 }
 
-/// Calculates intersection points for a quad and line
+/// Calculates the contact for a sprite and foothold
 pub fn collide_sprite_foothold(
     position: &Vec3,
     width: f32,
     height: f32,
     foothold: &Foothold,
-) -> HashMap<CollisionType, Vec2> {
+) -> Option<Contact> {
     let mut collisions = HashMap::new();
 
     let top_left = Vec3::new(position.x - width / 2.0, position.y + height / 2.0, 0.0);
@@ -56,135 +269,116 @@ pub fn collide_sprite_foothold(
     let bottom_left = Vec2::new(position.x - width / 2.0, position.y - height / 2.0);
     let bottom_right = Vec2::new(position.x + width / 2.0, position.y - height / 2.0);
     let bottom_center = Vec2::new(position.x, position.y - height / 2.0);
+    let (start, end) = (foothold.start(), foothold.end());
 
     if let Some(point) = collide_segment_segment(
-        foothold.x1,
-        foothold.y1,
-        foothold.x2,
-        foothold.y2,
+        start.x,
+        start.y,
+        end.x,
+        end.y,
         top_left.x,
         top_left.y,
         bottom_left.x,
         bottom_left.y,
-    ) {
+    )
+    .point()
+    {
         collisions.insert(CollisionType::Left, point);
     }
 
     if let Some(point) = collide_segment_segment(
-        foothold.x1,
-        foothold.y1,
-        foothold.x2,
-        foothold.y2,
+        start.x,
+        start.y,
+        end.x,
+        end.y,
         top_right.x,
         top_right.y,
         bottom_right.x,
         bottom_right.y,
-    ) {
+    )
+    .point()
+    {
         collisions.insert(CollisionType::Right, point);
     }
 
     if let Some(point) = collide_segment_segment(
-        foothold.x1,
-        foothold.y1,
-        foothold.x2,
-        foothold.y2,
+        start.x,
+        start.y,
+        end.x,
+        end.y,
         bottom_left.x,
         bottom_left.y,
         bottom_right.x,
         bottom_right.y,
-    ) {
+    )
+    .point()
+    {
         collisions.insert(CollisionType::Bottom, point);
     }
 
-    if collide_segment_point(
-        foothold.x1,
-        foothold.y1,
-        foothold.x2,
-        foothold.y2,
-        bottom_left.x,
-        bottom_left.y,
-    ) {
+    if collide_segment_point(start.x, start.y, end.x, end.y, bottom_left.x, bottom_left.y).is_some()
+    {
         collisions.insert(CollisionType::Bottom, bottom_left);
     }
 
     if collide_segment_point(
-        foothold.x1,
-        foothold.y1,
-        foothold.x2,
-        foothold.y2,
+        start.x,
+        start.y,
+        end.x,
+        end.y,
         bottom_right.x,
         bottom_right.y,
-    ) {
+    )
+    .is_some()
+    {
         collisions.insert(CollisionType::Bottom, bottom_right);
     }
 
     if collide_segment_point(
-        foothold.x1,
-        foothold.y1,
-        foothold.x2,
-        foothold.y2,
+        start.x,
+        start.y,
+        end.x,
+        end.y,
         bottom_center.x,
         bottom_center.y,
-    ) {
+    )
+    .is_some()
+    {
         collisions.insert(CollisionType::Bottom, bottom_center);
     }
 
-    collisions
-}
-
-/// Calculates the intersection point for 2 lines by their points
-/// Input:
-///   Line A: (x1, y1) to (x2, y2)
-///   Line B: (x3, y3) to (x4, y4)
-/// Output:
-///   Point: (x, y)
-pub fn collide_segment_segment(
-    x1: f32,
-    y1: f32,
-    x2: f32,
-    y2: f32,
-    x3: f32,
-    y3: f32,
-    x4: f32,
-    y4: f32,
-) -> Option<Vec2> {
-    // calculate the distance to intersection point
-    let num_a = (x4 - x3) * (y1 - y3) - (y4 - y3) * (x1 - x3);
-    let den_a = (y4 - y3) * (x2 - x1) - (x4 - x3) * (y2 - y1);
-    let u_a = num_a / den_a;
-
-    let num_b = (x2 - x1) * (y1 - y3) - (y2 - y1) * (x1 - x3);
-    let den_b = (y4 - y3) * (x2 - x1) - (x4 - x3) * (y2 - y1);
-    let u_b = num_b / den_b;
-
-    // if u_a and u_b are between 0.0 and 1.0, lines are colliding
-    if u_a >= 0.0 && u_a <= 1.0 && u_b >= 0.0 && u_b <= 1.0 {
-        let x = x1 + (u_a * (x2 - x1));
-        let y = y1 + (u_a * (y2 - y1));
-        Some(Vec2::new(x, y))
-    } else {
-        None
+    if collisions.is_empty() {
+        return None;
     }
+
+    let (normal, depth) = separate_box_segment(
+        Vec2::new(bottom_left.x, bottom_left.y),
+        Vec2::new(top_right.x, top_right.y),
+        start,
+        end,
+    );
+
+    Some(Contact {
+        foothold_id: foothold.id,
+        contact: QuadContact {
+            points: collisions,
+            normal,
+            depth,
+        },
+    })
 }
 
-/// Calculates the intersection point for 2 lines by their points
+/// Calculates the minimum translation separating a box from a segment, by testing
+/// the box axes and the segment normal
 /// Input:
+///   Box: min (x, y) to max (x, y)
 ///   Line: (x1, y1) to (x2, y2)
-///   Point: (x, y)
 /// Output:
-///   Bool: True if the point lies on the segment
-pub fn collide_segment_point(x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) -> bool {
-    // Get the distance between the 2 points on the segment
-    let len = Vec2::new(x1, y1).distance(Vec2::new(x2, y2));
-
-    // Get the distance between the 2 points on the segment and the target point
-    let d1 = Vec2::new(x, y).distance(Vec2::new(x1, y1));
-    let d2 = Vec2::new(x, y).distance(Vec2::new(x2, y2));
-
-    const BUFFER: f32 = 0.1;
-    if d1 + d2 >= len - BUFFER && d1 + d2 <= len + BUFFER {
-        true
-    } else {
-        false
-    }
+///   Normal: Unit axis pointing from the segment to the box
+///   Depth: Distance to move the box along the normal, 0.0 if only touching
+pub fn separate_box_segment(min: Vec2, max: Vec2, p1: Vec2, p2: Vec2) -> (Vec2, f32) {
+    separate_quad_line(
+        &Quad2d::from_min_max(min, max),
+        &Line2d::from_points(p1, p2),
+    )
 }
@@ -1,15 +1,29 @@
 use bevy::prelude::*;
+use geometry::{Surface, SurfaceSegment};
 
 /// Represents a foothold as a set of points.
+/// The geometry of the segment is computed once, when the foothold is created.
 #[derive(Clone, Component, Debug)]
 pub struct Foothold {
-    pub x1: f32,
-    pub y1: f32,
-    pub x2: f32,
-    pub y2: f32,
+    pub id: u32,
+    segment: SurfaceSegment,
 }
 
 impl Foothold {
+    /// Creates a foothold from its first and last points.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The id of the foothold.
+    /// * `start`: The left point of the foothold.
+    /// * `end`: The right point of the foothold.
+    pub fn new(id: u32, start: Vec2, end: Vec2) -> Self {
+        Foothold {
+            id,
+            segment: SurfaceSegment::new(start, end),
+        }
+    }
+
     /// Gets the y coordinate if x is within the range of points of this foothold.
     ///
     /// # Arguments
@@ -25,14 +39,7 @@ impl Foothold {
     /// ```
     ///
     pub fn get_y_at_x(&self, x: f32) -> Option<f32> {
-        // Check if 2 points contain x
-        if x >= self.x1 && x <= self.x2 {
-            let slope = (self.y2 - self.y1) / (self.x2 - self.x1);
-            let y = self.y1 + ((self.x1 + x) * slope);
-            return Some(y);
-        }
-
-        None
+        self.segment.y_at(x)
     }
 
     /// Gets the angle if x is within the range of points of this foothold.
@@ -50,12 +57,25 @@ impl Foothold {
     /// };
     /// ```
     pub fn get_angle_at_x(&self, x: f32) -> Option<f32> {
-        // Check if 2 points contain x
-        if x >= self.x1 && x <= self.x2 {
-            let slope = (self.y2 - self.y1) / (self.x2 - self.x1);
-            let angle = slope.atan();
-            return Some(angle);
-        }
-        None
+        self.segment.surface_at(x).map(|surface| surface.angle)
+    }
+
+    /// Gets the surface if x is within the range of points of this foothold.
+    ///
+    /// # Arguments
+    ///
+    /// * `x`: The value to evaluate for the surface.
+    pub fn get_surface_at_x(&self, x: f32) -> Option<Surface> {
+        self.segment.surface_at(x)
+    }
+
+    /// Gets the first point of this foothold.
+    pub fn start(&self) -> Vec2 {
+        self.segment.start
+    }
+
+    /// Gets the last point of this foothold.
+    pub fn end(&self) -> Vec2 {
+        self.segment.end
     }
 }
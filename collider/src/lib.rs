@@ -1,9 +1,11 @@
+pub mod alpha;
 pub mod camera;
 pub mod collision;
 pub mod foothold;
 pub mod player;
 pub mod render;
 
+pub use alpha::*;
 pub use camera::*;
 pub use collision::*;
 pub use foothold::*;
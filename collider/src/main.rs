@@ -7,7 +7,9 @@ fn main() {
         .add_plugin(CameraPlugin)
         .add_plugin(RenderPlugin)
         .add_plugin(PlayerPlugin)
+        .add_plugin(AlphaPlugin)
         .add_plugin(CollisionPlugin)
+        .add_plugin(CollisionDebugPlugin)
         .add_startup_system(setup)
         .add_startup_system(new_player_follow_camera)
         .run();
@@ -29,12 +31,7 @@ fn setup(mut commands: Commands) {
     ];
 
     for it in 1..points.len() {
-        let foothold = Foothold {
-            x1: points[it - 1].x,
-            y1: points[it - 1].y,
-            x2: points[it].x,
-            y2: points[it].y,
-        };
+        let foothold = Foothold::new(it as u32, points[it - 1], points[it]);
 
         commands
             .spawn()
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use super::render::*;
+use super::{collision::*, render::*};
 
 #[derive(Clone, Component, Debug)]
 pub struct Player;
@@ -10,6 +10,7 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system(spawn_player);
+        // Sets the velocity before the physics stage moves the player
         app.add_system(player_movement_system);
     }
 }
@@ -23,30 +24,40 @@ fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..Default::default()
         })
         .insert(Player)
+        .insert(Velocity::default())
+        // Drawn between physics steps, the velocity moves it instead of the rigid body
+        .insert(RigidBody::default())
+        .insert(PreviousStep::default())
         .insert(RenderColor::default());
 }
 
+/// Speed of the player in pixels per second.
+const MOVEMENT_SPEED: f32 = 60.0;
+
 fn player_movement_system(
     keyboard_input: Res<Input<KeyCode>>,
-    mut player: Query<(&mut Transform, &mut Sprite), (With<Transform>, With<Sprite>, With<Player>)>,
+    mut player: Query<(&mut Velocity, &mut Sprite), (With<Velocity>, With<Sprite>, With<Player>)>,
 ) {
     if player.is_empty() {
         return;
     }
-    let (mut transform, mut sprite) = player.single_mut();
+    let (mut velocity, mut sprite) = player.single_mut();
 
+    let mut direction = Vec2::ZERO;
     if keyboard_input.pressed(KeyCode::Left) {
-        transform.translation.x -= 1.0;
+        direction.x -= 1.0;
         sprite.flip_x = false;
     }
     if keyboard_input.pressed(KeyCode::Right) {
-        transform.translation.x += 1.0;
+        direction.x += 1.0;
         sprite.flip_x = true;
     }
     if keyboard_input.pressed(KeyCode::Up) {
-        transform.translation.y += 1.0;
+        direction.y += 1.0;
     }
     if keyboard_input.pressed(KeyCode::Down) {
-        transform.translation.y -= 1.0;
+        direction.y -= 1.0;
     }
+
+    velocity.0 = direction * MOVEMENT_SPEED;
 }
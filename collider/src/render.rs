@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use bevy_prototype_debug_lines::*;
+use std::collections::HashSet;
 
-use super::foothold::*;
+use super::{alpha::*, collision::*, foothold::*};
 
 const DEFAULT_COLOR: Color = Color::WHITE;
 const DEFAULT_PALETTE: [Color; 37] = [
@@ -76,21 +77,55 @@ impl Plugin for RenderPlugin {
     }
 }
 
+/// Plugin that colors footholds red while any sprite touches them.
+pub struct CollisionDebugPlugin;
+
+impl Plugin for CollisionDebugPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        // Collisions are detected in the physics stage, which runs before this one
+        app.add_system_to_stage(CoreStage::PostUpdate, collision_debug_system);
+    }
+}
+
+fn collision_debug_system(
+    states: Query<&CollisionState>,
+    mut footholds: Query<(Entity, &mut RenderColor), (With<Foothold>, With<RenderColor>)>,
+) {
+    // Union of the footholds touched by every sprite
+    let touched: HashSet<Entity> = states.iter().flat_map(|state| state.footholds()).collect();
+
+    for (entity, mut render_color) in footholds.iter_mut() {
+        let color = if touched.contains(&entity) {
+            Color::RED
+        } else {
+            Color::WHITE
+        };
+        if render_color.color != color {
+            render_color.color = color;
+        }
+    }
+}
+
 fn render_sprites_system(
     mut debug_lines: ResMut<DebugLines>,
     images: Res<Assets<Image>>,
     sprites: Query<
-        (&Transform, &Handle<Image>, &RenderColor),
+        (
+            &Transform,
+            &Sprite,
+            &Handle<Image>,
+            Option<&SpriteCollider>,
+            &RenderColor,
+        ),
         (With<Transform>, With<Sprite>, With<RenderColor>),
     >,
 ) {
-    for (transform, texture, render_color) in sprites.iter() {
-        let position = transform.translation;
+    for (transform, sprite, texture, collider, render_color) in sprites.iter() {
         let color = render_color.color;
 
         if let Some(image) = images.get(texture) {
-            let width = image.texture_descriptor.size.width as f32;
-            let height = image.texture_descriptor.size.height as f32;
+            let (position, width, height) =
+                sprite_bounds(transform.translation, sprite, image, collider);
 
             let top_left = Vec3::new(position.x - width / 2.0, position.y + height / 2.0, 0.0);
             let top_right = Vec3::new(position.x + width / 2.0, position.y + height / 2.0, 0.0);
@@ -111,8 +146,8 @@ fn render_footholds_system(
 ) {
     for (foothold, render_color) in footholds.iter() {
         let color = render_color.color;
-        let p1 = Vec3::new(foothold.x1, foothold.y1, 0.0);
-        let p2 = Vec3::new(foothold.x2, foothold.y2, 0.0);
+        let p1 = foothold.start().extend(0.0);
+        let p2 = foothold.end().extend(0.0);
         debug_lines.line_colored(p1, p2, 0., color);
     }
 }
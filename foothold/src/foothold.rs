@@ -1,73 +1,415 @@
-use bevy::prelude::*;
-use std::collections::HashMap;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use geometry::{Surface, SurfaceSegment};
+use shared::{CollisionGroups, PreviousStep};
+use std::{cmp::Ordering, collections::HashMap, error::Error, fmt};
 
+/// Indexes every foothold entity by id, and allocates ids that are unique across maps.
+///
+/// The `Foothold` component is the only copy of a foothold. The container keeps the
+/// entity handle and the geometry derived from it, refreshed whenever the component changes.
 #[derive(Default)]
 pub struct FootholdContainer {
-    pub data: HashMap<u32, Foothold>,
+    data: HashMap<FootholdId, FootholdEntry>,
+    entities: HashMap<Entity, FootholdId>,
+    next_id: u32,
 }
 
-/// Represents a foothold as a set of points.
-#[derive(Clone, Component, Debug)]
-pub struct Foothold {
-    pub id: u32,
-    pub x1: f32,
-    pub y1: f32,
-    pub x2: f32,
-    pub y2: f32,
-    pub prev: u32,
-    pub next: u32,
-}
+impl FootholdContainer {
+    /// Allocates an id that no other foothold in this container uses.
+    pub fn allocate_id(&mut self) -> FootholdId {
+        let id = FootholdId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Inserts or refreshes the foothold of an entity, precomputing its geometry.
+    /// Fails if another entity already uses the same id.
+    pub fn insert(&mut self, entity: Entity, foothold: &Foothold) -> Result<(), FootholdError> {
+        if let Some(existing) = self.data.get(&foothold.id) {
+            if existing.entity != entity {
+                return Err(FootholdError::DuplicateId(foothold.id));
+            }
+        }
+
+        // The id of an existing foothold was edited
+        if let Some(old_id) = self.entities.insert(entity, foothold.id) {
+            if old_id != foothold.id {
+                self.data.remove(&old_id);
+            }
+        }
+
+        // Keep allocated ids ahead of any explicitly chosen ids
+        self.next_id = self.next_id.max(foothold.id.0 + 1);
+
+        self.data.insert(
+            foothold.id,
+            FootholdEntry {
+                id: foothold.id,
+                entity,
+                layer: foothold.layer,
+                geometry: FootholdGeometry::from(foothold),
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes a foothold by id.
+    pub fn remove(&mut self, id: FootholdId) -> Option<FootholdEntry> {
+        let entry = self.data.remove(&id)?;
+        self.entities.remove(&entry.entity);
+        Some(entry)
+    }
+
+    /// Removes the foothold of an entity.
+    pub fn remove_entity(&mut self, entity: Entity) -> Option<FootholdEntry> {
+        let id = self.entities.remove(&entity)?;
+        self.data.remove(&id)
+    }
+
+    /// Removes every foothold. Ids are not reused afterwards.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.entities.clear();
+    }
+
+    /// Iterates over every foothold.
+    pub fn iter(&self) -> impl Iterator<Item = &FootholdEntry> {
+        self.data.values()
+    }
+
+    /// Gets the id of the foothold of an entity.
+    pub fn id(&self, entity: Entity) -> Option<FootholdId> {
+        self.entities.get(&entity).copied()
+    }
+
+    /// Gets the entity of a foothold by id.
+    pub fn entity(&self, id: FootholdId) -> Option<Entity> {
+        self.data.get(&id).map(|entry| entry.entity)
+    }
 
-impl Foothold {
-    /// Gets the y coordinate if x is within the range of points of this foothold.
+    /// Gets the precomputed geometry of a foothold by id.
+    pub fn geometry(&self, id: FootholdId) -> Option<&FootholdGeometry> {
+        self.data.get(&id).map(|entry| &entry.geometry)
+    }
+
+    /// Gets the y coordinate of a foothold at x.
     ///
     /// # Arguments
     ///
+    /// * `id`: The foothold id.
     /// * `x`: The value to evaluate for y.
+    pub fn y_at(&self, id: FootholdId, x: f32) -> Option<f32> {
+        self.geometry(id).and_then(|geometry| geometry.y_at(x))
+    }
+
+    /// Gets the surface of a foothold at x.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The foothold id.
+    /// * `x`: The value to evaluate the surface for.
     ///
     /// # Examples
+    /// ```ignore
+    /// if let Some(surface) = container.surface_at(FootholdId(1), 5.0) {
+    ///     println!("{} {} {}", surface.y, surface.angle, surface.normal);
+    /// }
     /// ```
-    /// match foothold.get_y_at_x(5.0) {
-    ///     Some(y) => { println!("{}", y) },
-    ///     None => { },
-    /// };
-    /// ```
+    pub fn surface_at(&self, id: FootholdId, x: f32) -> Option<FootholdSurface> {
+        self.geometry(id)
+            .and_then(|geometry| geometry.surface_at(x))
+    }
+
+    /// Gets the first foothold straight down from a point.
     ///
-    pub fn get_y_at_x(&self, x: f32) -> Option<f32> {
-        // Check if 2 points contain x
-        if x >= self.x1 && x <= self.x2 {
-            let slope = (self.y2 - self.y1) / (self.x2 - self.x1);
-            let y = self.y2 + ((x - self.x2) * slope);
-            return Some(y);
-        }
+    /// # Arguments
+    ///
+    /// * `point`: The point to look down from.
+    /// * `max_distance`: The maximum distance below the point to look for ground.
+    /// * `layer_filter`: Only consider footholds in this layer, if any.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// if let Some(ground) = container.ground_below(Vec2::new(0.0, 200.0), 500.0, None) {
+    ///     println!("fh({}): {}", ground.id, ground.y);
+    /// }
+    /// ```
+    pub fn ground_below(
+        &self,
+        point: Vec2,
+        max_distance: f32,
+        layer_filter: Option<u32>,
+    ) -> Option<GroundHit> {
+        self.iter()
+            .filter(|entry| layer_filter.map_or(true, |layer| entry.layer == layer))
+            .filter_map(|entry| {
+                entry
+                    .geometry
+                    .y_at(point.x)
+                    .map(|y| GroundHit { id: entry.id, y })
+            })
+            .filter(|hit| hit.y <= point.y && point.y - hit.y <= max_distance)
+            .max_by(|a, b| a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+    }
+}
+
+/// Represents the ground found below a point.
+#[derive(Clone, Copy, Debug)]
+pub struct GroundHit {
+    pub id: FootholdId,
+    pub y: f32,
+}
+
+/// Represents a foothold stored in the container.
+#[derive(Clone, Debug)]
+pub struct FootholdEntry {
+    pub id: FootholdId,
+    pub entity: Entity,
+    pub layer: u32,
+    pub geometry: FootholdGeometry,
+}
+
+/// Resolves foothold ids to their components through the ECS.
+#[derive(SystemParam)]
+pub struct Footholds<'w, 's> {
+    pub container: Res<'w, FootholdContainer>,
+    pub query: Query<'w, 's, &'static Foothold>,
+    pub groups: Query<'w, 's, &'static CollisionGroups, With<Foothold>>,
+}
+
+impl<'w, 's> Footholds<'w, 's> {
+    /// Gets a foothold by id.
+    pub fn get(&self, id: FootholdId) -> Option<&Foothold> {
+        let entity = self.container.entity(id)?;
+        self.query.get(entity).ok()
+    }
+
+    /// Gets the collision groups of a foothold by id.
+    /// Footholds without the component are only in `GROUP_FOOTHOLD`.
+    pub fn groups(&self, id: FootholdId) -> CollisionGroups {
+        self.container
+            .entity(id)
+            .and_then(|entity| self.groups.get(entity).ok())
+            .copied()
+            .unwrap_or_else(CollisionGroups::foothold)
+    }
 
-        None
+    /// Returns true if a foothold is solid for an entity in the given groups.
+    pub fn interacts_with(&self, id: FootholdId, groups: &CollisionGroups) -> bool {
+        groups.interacts_with(&self.groups(id))
     }
 }
 
-/// Represents a foothold id attached to an entity.
+/// Represents the geometry of a foothold, computed once when it enters the container.
+pub type FootholdGeometry = SurfaceSegment;
+
+impl From<&Foothold> for FootholdGeometry {
+    fn from(foothold: &Foothold) -> Self {
+        SurfaceSegment::new(
+            Vec2::new(foothold.x1, foothold.y1),
+            Vec2::new(foothold.x2, foothold.y2),
+        )
+    }
+}
+
+/// Represents the surface of a foothold at a given x.
+/// The angle is in radians and the normal points up for footholds running left to right.
+pub type FootholdSurface = Surface;
+
+/// Represents a foothold as a set of points.
+///
+/// Evaluate it through `FootholdContainer::y_at` and `surface_at`, which cache its geometry.
 #[derive(Clone, Component, Debug)]
+pub struct Foothold {
+    pub id: FootholdId,
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+    pub prev: Option<FootholdId>,
+    pub next: Option<FootholdId>,
+    pub layer: u32,
+}
+
+/// Represents a foothold id attached to an entity.
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FootholdId(pub u32);
 
+impl fmt::Display for FootholdId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Represents the errors raised by the foothold container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FootholdError {
+    DuplicateId(FootholdId),
+}
+
+impl fmt::Display for FootholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FootholdError::DuplicateId(id) => write!(f, "foothold({}): duplicate id", id),
+        }
+    }
+}
+
+impl Error for FootholdError {}
+
+/// Label of the system that indexes new or edited footholds, for ordering systems that spawn
+/// or despawn them.
+pub const FOOTHOLD_SYNC_LABEL: &str = "foothold_sync";
+
+/// Distance below an entity searched when snapping it to the ground.
+pub const SNAP_TO_GROUND_DISTANCE: f32 = 1000.0;
+
+/// Marks an entity to be placed on the ground below it once footholds are available.
+#[derive(Clone, Component, Debug, Default)]
+pub struct SnapToGround {
+    pub layer: Option<u32>,
+}
+
 pub struct FootholdPlugin;
 
 impl Plugin for FootholdPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FootholdContainer>();
-        app.add_system(add_new_footholds);
+        app.add_system(sync_changed_footholds.label(FOOTHOLD_SYNC_LABEL))
+            .add_system(snap_to_ground_system);
+        app.add_system_to_stage(CoreStage::PostUpdate, remove_despawned_footholds);
+    }
+}
+
+/// detect new or edited footholds and index them in the resource
+pub(crate) fn sync_changed_footholds(
+    mut commands: Commands,
+    mut container: ResMut<FootholdContainer>,
+    query: Query<(Entity, &Foothold), Changed<Foothold>>,
+) {
+    for (entity, foothold) in query.iter() {
+        // Overwriting would silently break the links of the existing foothold,
+        // so the duplicate is dropped and the original is kept
+        if let Err(err) = container.insert(entity, foothold) {
+            error!("{}, despawning entity {:?}", err, entity);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        info!("foothold({}): indexed", foothold.id);
     }
 }
 
-/// detect new footholds and add it to the resource
-fn add_new_footholds(
+/// detect removed footholds and drop them from the resource
+fn remove_despawned_footholds(
     mut container: ResMut<FootholdContainer>,
-    query: Query<&Foothold, Added<Foothold>>,
+    removed: RemovedComponents<Foothold>,
 ) {
-    for foothold in query.iter() {
-        // Only insert footholds with ids 1 or greater
-        if foothold.id != 0 {
-            info!("foothold({}): inserted", foothold.id);
-            container.data.insert(foothold.id, foothold.clone());
+    for entity in removed.iter() {
+        if let Some(entry) = container.remove_entity(entity) {
+            info!("foothold({}): removed", entry.id);
+        }
+    }
+}
+
+/// place entities marked with snap to ground on the foothold below them
+fn snap_to_ground_system(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    container: Res<FootholdContainer>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Handle<Image>,
+            &SnapToGround,
+            Option<&FootholdId>,
+            Option<&mut PreviousStep>,
+        ),
+        With<SnapToGround>,
+    >,
+) {
+    for (entity, mut transform, texture, snap, foothold_id, previous) in query.iter_mut() {
+        // Already standing on a foothold
+        if foothold_id.is_some() {
+            commands.entity(entity).remove::<SnapToGround>();
+            continue;
+        }
+
+        if let Some(image) = images.get(texture) {
+            let half_height = image.texture_descriptor.size.height as f32 / 2.0;
+            let bottom = Vec2::new(
+                transform.translation.x,
+                transform.translation.y - half_height,
+            );
+
+            if let Some(ground) =
+                container.ground_below(bottom, SNAP_TO_GROUND_DISTANCE, snap.layer)
+            {
+                info!("fh({}): snapped", ground.id);
+                transform.translation.y = ground.y + half_height;
+                // Teleport instead of drawing the entity falling onto the ground
+                if let Some(mut previous) = previous {
+                    *previous = PreviousStep::new(transform.translation.truncate());
+                }
+                commands
+                    .entity(entity)
+                    .insert(ground.id)
+                    .remove::<SnapToGround>();
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn foothold(id: u32, x1: f32, x2: f32) -> Foothold {
+        Foothold {
+            id: FootholdId(id),
+            x1,
+            y1: 0.0,
+            x2,
+            y2: 0.0,
+            prev: None,
+            next: None,
+            layer: 0,
+        }
+    }
+
+    #[test]
+    fn insert_duplicate_keeps_original() {
+        let mut world = World::default();
+        let original = world.spawn().id();
+        let duplicate = world.spawn().id();
+
+        let mut container = FootholdContainer::default();
+        assert_eq!(container.insert(original, &foothold(1, 0.0, 10.0)), Ok(()));
+        assert_eq!(
+            container.insert(duplicate, &foothold(1, 20.0, 30.0)),
+            Err(FootholdError::DuplicateId(FootholdId(1)))
+        );
+
+        assert_eq!(container.entity(FootholdId(1)), Some(original));
+        assert_eq!(container.id(duplicate), None);
+        assert_eq!(container.y_at(FootholdId(1), 5.0), Some(0.0));
+        assert_eq!(container.y_at(FootholdId(1), 25.0), None);
+    }
+
+    #[test]
+    fn sync_despawns_duplicate() {
+        let mut world = World::default();
+        world.init_resource::<FootholdContainer>();
+        let original = world.spawn().insert(foothold(1, 0.0, 10.0)).id();
+        let duplicate = world.spawn().insert(foothold(1, 20.0, 30.0)).id();
+
+        let mut stage = SystemStage::single(sync_changed_footholds);
+        stage.run(&mut world);
+
+        let container = world.get_resource::<FootholdContainer>().unwrap();
+        assert_eq!(container.entity(FootholdId(1)), Some(original));
+        assert_eq!(container.iter().count(), 1);
+        assert!(world.get_entity(duplicate).is_none());
+        assert!(world.get_entity(original).is_some());
+    }
+}
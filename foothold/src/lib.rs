@@ -1,9 +1,12 @@
 pub mod foothold;
+pub mod map;
 pub mod player;
 pub mod rigid_body;
 pub mod util;
 
 pub use foothold::*;
+pub use map::*;
 pub use player::*;
 pub use rigid_body::*;
+pub use shared::groups::*;
 pub use util::*;
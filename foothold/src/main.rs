@@ -5,15 +5,17 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugin(FootholdPlugin)
+        .add_plugin(MapPlugin)
         .add_plugin(CameraPlugin)
         .add_plugin(RenderPlugin)
         .add_plugin(PlayerPlugin)
         .add_startup_system(setup)
         .add_startup_system(new_player_follow_camera)
+        .add_system(map_select_system)
         .run();
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut registry: ResMut<MapRegistry>, mut change_map: EventWriter<ChangeMap>) {
     let top: [Vec2; 11] = [
         Vec2::new(-500.0, 100.0),
         Vec2::new(-400.0, 125.0),
@@ -28,27 +30,6 @@ fn setup(mut commands: Commands) {
         Vec2::new(500.0, 100.0),
     ];
 
-    for (pos, it) in (1..top.len()).enumerate() {
-        let id = (pos + 1) as u32; // 1-indexed
-        let prev = if pos > 0 { id - 1 } else { 0 };
-        let next = if pos < top.len() { id + 1 } else { 0 };
-
-        let foothold = Foothold {
-            id,
-            x1: top[it - 1].x,
-            y1: top[it - 1].y,
-            x2: top[it].x,
-            y2: top[it].y,
-            prev,
-            next,
-        };
-
-        commands
-            .spawn()
-            .insert(foothold)
-            .insert(RenderColor::from(Color::WHITE));
-    }
-
     let bottom: [Vec2; 11] = [
         Vec2::new(-500.0, 0.0),
         Vec2::new(-400.0, 25.0),
@@ -63,28 +44,6 @@ fn setup(mut commands: Commands) {
         Vec2::new(500.0, 0.0),
     ];
 
-    let top_len = top.len();
-    for (pos, it) in (1..bottom.len()).enumerate() {
-        let id = (top_len + pos + 1) as u32; // 1-indexed
-        let prev = if pos > 0 { id - 1 } else { 0 };
-        let next = if pos < bottom.len() { id + 1 } else { 0 };
-
-        let foothold = Foothold {
-            id,
-            x1: bottom[it - 1].x,
-            y1: bottom[it - 1].y,
-            x2: bottom[it].x,
-            y2: bottom[it].y,
-            prev,
-            next,
-        };
-
-        commands
-            .spawn()
-            .insert(foothold)
-            .insert(RenderColor::from(Color::WHITE));
-    }
-
     let slope: [Vec2; 6] = [
         Vec2::new(400.0, 0.0),
         Vec2::new(500.0, 25.0),
@@ -94,25 +53,46 @@ fn setup(mut commands: Commands) {
         Vec2::new(900.0, 0.0),
     ];
 
-    let bottom_len = bottom.len();
-    for (pos, it) in (1..slope.len()).enumerate() {
-        let id = (top_len + bottom_len + pos + 1) as u32; // 1-indexed
-        let prev = if pos > 0 { id - 1 } else { 0 };
-        let next = if pos < slope.len() { id + 1 } else { 0 };
+    let flat: [Vec2; 3] = [
+        Vec2::new(-500.0, 0.0),
+        Vec2::new(0.0, 0.0),
+        Vec2::new(500.0, 0.0),
+    ];
+
+    registry.insert(
+        MapId(0),
+        Map::default()
+            .with_footholds(0, &top)
+            .with_footholds(1, &bottom)
+            .with_footholds(2, &slope)
+            .with_spawn(Vec2::new(0.0, 200.0)),
+    );
 
-        let foothold = Foothold {
-            id,
-            x1: slope[it - 1].x,
-            y1: slope[it - 1].y,
-            x2: slope[it].x,
-            y2: slope[it].y,
-            prev,
-            next,
-        };
+    registry.insert(
+        MapId(1),
+        Map::default()
+            .with_footholds(0, &flat)
+            .with_spawn(Vec2::new(0.0, 100.0)),
+    );
 
-        commands
-            .spawn()
-            .insert(foothold)
-            .insert(RenderColor::from(Color::WHITE));
+    change_map.send(ChangeMap {
+        map: MapId(0),
+        spawn: 0,
+    });
+}
+
+/// Switch between the registered maps with the number keys.
+fn map_select_system(keyboard_input: Res<Input<KeyCode>>, mut change_map: EventWriter<ChangeMap>) {
+    if keyboard_input.just_pressed(KeyCode::Key1) {
+        change_map.send(ChangeMap {
+            map: MapId(0),
+            spawn: 0,
+        });
+    }
+    if keyboard_input.just_pressed(KeyCode::Key2) {
+        change_map.send(ChangeMap {
+            map: MapId(1),
+            spawn: 0,
+        });
     }
 }
@@ -0,0 +1,509 @@
+use crate::*;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Represents the map an entity belongs to.
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq, Hash)]
+pub struct MapId(pub u32);
+
+/// Represents a chain of connected footholds in a layer.
+#[derive(Clone, Debug, Default)]
+pub struct FootholdChain {
+    pub layer: u32,
+    pub points: Vec<Vec2>,
+}
+
+/// Represents a map as a set of foothold chains and spawn points.
+#[derive(Clone, Debug, Default)]
+pub struct Map {
+    pub footholds: Vec<FootholdChain>,
+    pub spawns: Vec<Vec2>,
+}
+
+impl Map {
+    /// Adds a chain of connected footholds to this map.
+    ///
+    /// # Arguments
+    ///
+    /// * `layer`: The layer every foothold of the chain is in.
+    /// * `points`: The points of the chain, from left to right.
+    pub fn with_footholds(mut self, layer: u32, points: &[Vec2]) -> Self {
+        self.footholds.push(FootholdChain {
+            layer,
+            points: points.to_vec(),
+        });
+        self
+    }
+
+    /// Adds a spawn point to this map.
+    ///
+    /// # Arguments
+    ///
+    /// * `spawn`: The position players are placed at.
+    pub fn with_spawn(mut self, spawn: Vec2) -> Self {
+        self.spawns.push(spawn);
+        self
+    }
+}
+
+/// Represents whether maps are simulated for one player or for every player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapMode {
+    /// Exactly one map is active, replaced through `ChangeMap`.
+    Client,
+    /// Several maps can be active at once, loaded through `LoadMap`.
+    Server,
+}
+
+impl Default for MapMode {
+    fn default() -> Self {
+        MapMode::Client
+    }
+}
+
+/// Holds every known map and the maps that are currently active.
+///
+/// A client keeps exactly one map active, a server may keep several.
+#[derive(Default)]
+pub struct MapRegistry {
+    pub maps: HashMap<MapId, Map>,
+    pub active: Vec<MapId>,
+    pub mode: MapMode,
+}
+
+impl MapRegistry {
+    /// Registers a map so it can be loaded later.
+    pub fn insert(&mut self, id: MapId, map: Map) {
+        self.maps.insert(id, map);
+    }
+
+    /// Gets a registered map.
+    pub fn get(&self, id: MapId) -> Option<&Map> {
+        self.maps.get(&id)
+    }
+
+    /// Returns true if the map is currently loaded.
+    pub fn is_active(&self, id: MapId) -> bool {
+        self.active.contains(&id)
+    }
+}
+
+/// Event that replaces every active map and places players at a spawn point.
+#[derive(Clone, Debug)]
+pub struct ChangeMap {
+    pub map: MapId,
+    pub spawn: usize,
+}
+
+/// Event that loads a map next to the active maps, only in `MapMode::Server`.
+#[derive(Clone, Debug)]
+pub struct LoadMap(pub MapId);
+
+/// Event that unloads an active map.
+#[derive(Clone, Debug)]
+pub struct UnloadMap(pub MapId);
+
+/// Label of the system that replaces the active maps.
+pub const MAP_CHANGE_LABEL: &str = "map_change";
+/// Label of the system that unloads maps, after maps are changed.
+pub const MAP_UNLOAD_LABEL: &str = "map_unload";
+/// Label of the system that loads maps, after maps are changed and unloaded.
+pub const MAP_LOAD_LABEL: &str = "map_load";
+
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MapRegistry>()
+            .add_event::<ChangeMap>()
+            .add_event::<LoadMap>()
+            .add_event::<UnloadMap>();
+
+        // Footholds are indexed before maps despawn them, so none are left in the container
+        app.add_system(
+            change_map_system
+                .label(MAP_CHANGE_LABEL)
+                .after(FOOTHOLD_SYNC_LABEL),
+        )
+        .add_system(
+            unload_map_system
+                .label(MAP_UNLOAD_LABEL)
+                .after(MAP_CHANGE_LABEL),
+        )
+        .add_system(
+            load_map_system
+                .label(MAP_LOAD_LABEL)
+                .after(MAP_UNLOAD_LABEL),
+        );
+    }
+}
+
+/// Unload all active maps, load the target map and move players to its spawn.
+fn change_map_system(
+    mut commands: Commands,
+    mut events: EventReader<ChangeMap>,
+    mut registry: ResMut<MapRegistry>,
+    mut container: ResMut<FootholdContainer>,
+    map_entities: Query<Entity, With<MapId>>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut RigidBody,
+            Option<&mut PreviousStep>,
+        ),
+        With<Player>,
+    >,
+) {
+    // Only the last request in a frame matters
+    let event = match events.iter().last() {
+        Some(event) => event,
+        None => return,
+    };
+
+    let map = match registry.get(event.map) {
+        Some(map) => map.clone(),
+        None => {
+            warn!("map({}): not registered", event.map.0);
+            return;
+        }
+    };
+
+    for entity in map_entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    container.clear();
+    registry.active.clear();
+
+    spawn_map(&mut commands, &mut container, event.map, &map);
+    registry.active.push(event.map);
+    info!("map({}): changed", event.map.0);
+
+    let spawn = match map.spawns.get(event.spawn) {
+        Some(&spawn) => spawn,
+        None => {
+            warn!(
+                "map({}): no spawn {}, using the first spawn",
+                event.map.0, event.spawn
+            );
+            map.spawns.first().copied().unwrap_or_default()
+        }
+    };
+    for (entity, mut transform, mut body, previous) in players.iter_mut() {
+        transform.translation.x = spawn.x;
+        transform.translation.y = spawn.y;
+        *body = RigidBody::default();
+        // Teleport instead of drawing the player between the maps
+        if let Some(mut previous) = previous {
+            *previous = PreviousStep::new(spawn);
+        }
+        commands
+            .entity(entity)
+            .remove::<FootholdId>()
+            .insert(SnapToGround::default());
+    }
+}
+
+/// Load maps next to the active maps.
+fn load_map_system(
+    mut commands: Commands,
+    mut events: EventReader<LoadMap>,
+    mut registry: ResMut<MapRegistry>,
+    mut container: ResMut<FootholdContainer>,
+) {
+    for LoadMap(id) in events.iter() {
+        // A client only ever has the map it changed to
+        if registry.mode != MapMode::Server {
+            warn!("map({}): loading needs server mode, use ChangeMap", id.0);
+            continue;
+        }
+        if registry.is_active(*id) {
+            continue;
+        }
+        if let Some(map) = registry.get(*id).cloned() {
+            spawn_map(&mut commands, &mut container, *id, &map);
+            registry.active.push(*id);
+            info!("map({}): loaded", id.0);
+        } else {
+            warn!("map({}): not registered", id.0);
+        }
+    }
+}
+
+/// Despawn the footholds of a map and remove them from the container.
+fn unload_map_system(
+    mut commands: Commands,
+    mut events: EventReader<UnloadMap>,
+    mut registry: ResMut<MapRegistry>,
+    mut container: ResMut<FootholdContainer>,
+    footholds: Query<(Entity, &MapId, Option<&Foothold>)>,
+) {
+    for UnloadMap(id) in events.iter() {
+        for (entity, map_id, foothold) in footholds.iter() {
+            if map_id != id {
+                continue;
+            }
+            if let Some(foothold) = foothold {
+                container.remove(foothold.id);
+            }
+            commands.entity(entity).despawn();
+        }
+        registry.active.retain(|active| active != id);
+        info!("map({}): unloaded", id.0);
+    }
+}
+
+/// Spawn the footholds of a map, linking each chain from left to right.
+/// Ids are allocated by the container so several maps can be loaded at once.
+fn spawn_map(commands: &mut Commands, container: &mut FootholdContainer, map_id: MapId, map: &Map) {
+    for chain in map.footholds.iter() {
+        let points = &chain.points;
+        let ids: Vec<FootholdId> = (1..points.len()).map(|_| container.allocate_id()).collect();
+
+        for (pos, it) in (1..points.len()).enumerate() {
+            let foothold = Foothold {
+                id: ids[pos],
+                x1: points[it - 1].x,
+                y1: points[it - 1].y,
+                x2: points[it].x,
+                y2: points[it].y,
+                prev: if pos > 0 { Some(ids[pos - 1]) } else { None },
+                next: ids.get(pos + 1).copied(),
+                layer: chain.layer,
+            };
+
+            commands
+                .spawn()
+                .insert(foothold)
+                .insert(map_id)
+                .insert(RenderColor::from(Color::WHITE));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::Events;
+
+    fn app(mode: MapMode) -> App {
+        let mut app = App::new();
+        app.add_plugin(MapPlugin)
+            .init_resource::<FootholdContainer>()
+            .add_system(sync_changed_footholds.label(FOOTHOLD_SYNC_LABEL));
+
+        let mut registry = app.world.get_resource_mut::<MapRegistry>().unwrap();
+        registry.mode = mode;
+        registry.insert(
+            MapId(0),
+            Map::default()
+                .with_footholds(0, &[Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)])
+                .with_spawn(Vec2::new(5.0, 50.0)),
+        );
+        registry.insert(
+            MapId(1),
+            Map::default()
+                .with_footholds(
+                    0,
+                    &[
+                        Vec2::new(0.0, 0.0),
+                        Vec2::new(10.0, 0.0),
+                        Vec2::new(20.0, 10.0),
+                    ],
+                )
+                .with_spawn(Vec2::new(15.0, 60.0))
+                .with_spawn(Vec2::new(2.0, 70.0)),
+        );
+        app
+    }
+
+    fn send<T: Send + Sync + 'static>(app: &mut App, event: T) {
+        app.world
+            .get_resource_mut::<Events<T>>()
+            .unwrap()
+            .send(event);
+    }
+
+    /// Runs the frame handling the events, then the frame indexing the spawned footholds.
+    fn update(app: &mut App) {
+        app.update();
+        app.update();
+    }
+
+    fn spawn_player(app: &mut App) -> Entity {
+        app.world
+            .spawn()
+            .insert(Transform::default())
+            .insert(RigidBody::default())
+            .insert(PreviousStep::default())
+            .insert(Player)
+            .id()
+    }
+
+    fn active(app: &App) -> Vec<MapId> {
+        app.world
+            .get_resource::<MapRegistry>()
+            .unwrap()
+            .active
+            .clone()
+    }
+
+    /// Gets the maps of the footholds in the container.
+    fn indexed_maps(app: &App) -> Vec<MapId> {
+        let container = app.world.get_resource::<FootholdContainer>().unwrap();
+        let mut maps: Vec<MapId> = container
+            .iter()
+            .map(|entry| *app.world.get::<MapId>(entry.entity).unwrap())
+            .collect();
+        maps.sort_by_key(|map| map.0);
+        maps
+    }
+
+    fn position(app: &App, entity: Entity) -> Vec2 {
+        app.world
+            .get::<Transform>(entity)
+            .unwrap()
+            .translation
+            .truncate()
+    }
+
+    #[test]
+    fn change_map_replaces_active_map() {
+        let mut app = app(MapMode::Client);
+        let player = spawn_player(&mut app);
+
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(0),
+                spawn: 0,
+            },
+        );
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(0)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(0)]);
+        assert_eq!(position(&app, player), Vec2::new(5.0, 50.0));
+
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(1),
+                spawn: 1,
+            },
+        );
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(1)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(1), MapId(1)]);
+        assert_eq!(position(&app, player), Vec2::new(2.0, 70.0));
+        assert_eq!(
+            app.world.get::<PreviousStep>(player).unwrap().position,
+            Vec2::new(2.0, 70.0)
+        );
+    }
+
+    #[test]
+    fn change_map_with_invalid_spawn_uses_first_spawn() {
+        let mut app = app(MapMode::Client);
+        let player = spawn_player(&mut app);
+
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(1),
+                spawn: 7,
+            },
+        );
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(1)]);
+        assert_eq!(position(&app, player), Vec2::new(15.0, 60.0));
+    }
+
+    #[test]
+    fn change_map_to_unregistered_map_keeps_active_map() {
+        let mut app = app(MapMode::Client);
+
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(0),
+                spawn: 0,
+            },
+        );
+        update(&mut app);
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(9),
+                spawn: 0,
+            },
+        );
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(0)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(0)]);
+    }
+
+    #[test]
+    fn load_map_is_rejected_on_client() {
+        let mut app = app(MapMode::Client);
+
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(0),
+                spawn: 0,
+            },
+        );
+        update(&mut app);
+        send(&mut app, LoadMap(MapId(1)));
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(0)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(0)]);
+    }
+
+    #[test]
+    fn load_map_in_same_frame_as_change_map() {
+        let mut app = app(MapMode::Server);
+
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(0),
+                spawn: 0,
+            },
+        );
+        send(&mut app, LoadMap(MapId(1)));
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(0), MapId(1)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(0), MapId(1), MapId(1)]);
+
+        // Changing map drops the loaded map along with the active one
+        send(
+            &mut app,
+            ChangeMap {
+                map: MapId(0),
+                spawn: 0,
+            },
+        );
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(0)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(0)]);
+    }
+
+    #[test]
+    fn unload_map_removes_its_footholds() {
+        let mut app = app(MapMode::Server);
+
+        send(&mut app, LoadMap(MapId(0)));
+        send(&mut app, LoadMap(MapId(1)));
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(0), MapId(1)]);
+
+        send(&mut app, UnloadMap(MapId(0)));
+        update(&mut app);
+        assert_eq!(active(&app), vec![MapId(1)]);
+        assert_eq!(indexed_maps(&app), vec![MapId(1), MapId(1)]);
+
+        let mut map_entities = app.world.query::<&MapId>();
+        assert!(map_entities.iter(&app.world).all(|map| *map == MapId(1)));
+    }
+}
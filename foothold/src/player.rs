@@ -8,43 +8,55 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(RigidBodyPlugin);
+        app.add_plugin(RigidBodyPlugin::<Transform>::default());
 
         app.add_startup_system(spawn_player);
-        app.add_system(player_movement_system)
-            .add_system(player_foothold_collision_system);
+        app.add_system(player_movement_system);
+        app.add_system_to_stage(
+            PhysicsStage,
+            player_foothold_collision_system.after(RIGID_BODY_LABEL),
+        );
     }
 }
 
 fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
     let texture = asset_server.load("player.png");
+    let transform = Transform::from_xyz(0.0, 200.0, 0.0);
 
     commands
         .spawn_bundle(SpriteBundle {
-            transform: Transform::from_xyz(0.0, 200.0, 0.0),
             texture,
+            transform,
             ..Default::default()
         })
         .insert(Player)
         .insert(RigidBody::default())
+        .insert(PreviousStep::new(transform.translation.truncate()))
+        .insert(SnapToGround::default())
+        .insert(CollisionGroups::new(GROUP_PLAYER, GROUP_ALL))
         .insert(RenderColor::default());
 }
 
 fn player_movement_system(
     mut commands: Commands,
     keyboard_input: Res<Input<KeyCode>>,
-    mut player: Query<(Entity, &mut RigidBody), (With<RigidBody>, With<Player>)>,
+    config: Res<PhysicsConfig>,
+    mut player: Query<
+        (Entity, &mut RigidBody, Option<&PhysicsOverrides>),
+        (With<RigidBody>, With<Player>),
+    >,
 ) {
     if player.is_empty() {
         return;
     }
-    let (entity, mut body) = player.single_mut();
+    let (entity, mut body, overrides) = player.single_mut();
+    let config = config.with_overrides(overrides);
 
     if keyboard_input.pressed(KeyCode::Left) {
-        body.acceleration.x = -MOVEMENT_SPEED;
+        body.acceleration.x = -config.movement_speed;
     }
     if keyboard_input.pressed(KeyCode::Right) {
-        body.acceleration.x = MOVEMENT_SPEED;
+        body.acceleration.x = config.movement_speed;
     }
 
     if !keyboard_input.pressed(KeyCode::Left) && !keyboard_input.pressed(KeyCode::Right) {
@@ -53,14 +65,14 @@ fn player_movement_system(
 
     if keyboard_input.pressed(KeyCode::LAlt) {
         commands.entity(entity).remove::<FootholdId>();
-        body.acceleration.y = JUMP_FORCE;
+        body.acceleration.y = config.jump_force;
     }
 }
 
 fn player_foothold_collision_system(
     mut commands: Commands,
     images: Res<Assets<Image>>,
-    footholds_container: Res<FootholdContainer>,
+    footholds: Footholds,
     mut player: Query<
         (
             Entity,
@@ -68,20 +80,24 @@ fn player_foothold_collision_system(
             &mut RigidBody,
             &Handle<Image>,
             Option<&FootholdId>,
+            Option<&CollisionGroups>,
         ),
         (With<Transform>, With<RigidBody>, With<Sprite>, With<Player>),
     >,
-    footholds: Query<&Foothold, With<Foothold>>,
 ) {
     if player.is_empty() {
         return;
     }
-    let (entity, mut transform, body, texture, foothold_id) = player.single_mut();
+    let (entity, mut transform, body, texture, foothold_id, groups) = player.single_mut();
+
+    // Only footholds the player's groups interact with are solid
+    let groups = groups.copied().unwrap_or_default();
 
     // Calculate the next position
+    let dt = PHYSICS_TIMESTEP as f32;
     let mut next_transform = transform.clone();
-    next_transform.translation += Vec3::new(body.velocity.x, body.velocity.y, 0.0)
-        + Vec3::new(0.5 * body.acceleration.x, 0.5 * body.acceleration.y, 0.0);
+    next_transform.translation +=
+        ((body.velocity * dt) + (0.5 * body.acceleration * dt * dt)).extend(0.0);
 
     if let Some(image) = images.get(texture) {
         let height = image.texture_descriptor.size.height as f32;
@@ -90,27 +106,25 @@ fn player_foothold_collision_system(
         let mut use_collision = false;
 
         // Footold exists: check nodes and update
-        if let Some(id) = foothold_id {
-            let curr = id.0;
-
-            if let Some(foothold) = footholds_container.data.get(&curr) {
-                if let Some(y) = foothold.get_y_at_x(next_transform.translation.x) {
+        if let Some(&curr) = foothold_id {
+            if !footholds.interacts_with(curr, &groups) {
+                info!("fh({}): filtered", curr);
+                commands.entity(entity).remove::<FootholdId>();
+                use_collision = true;
+            } else if let Some(foothold) = footholds.get(curr) {
+                let x = next_transform.translation.x;
+
+                if let Some(y) = footholds.container.y_at(curr, x) {
                     position_limit_ground_y(&mut (next_transform.translation), height, y);
-                } else if let Some(y) = container_get_y_at_x(
-                    &footholds_container,
-                    foothold.prev,
-                    next_transform.translation.x,
-                ) {
-                    info!("fh({}): previous({})", foothold.id, foothold.prev);
-                    commands.entity(entity).insert(FootholdId(foothold.prev));
+                } else if let Some((prev, y)) = linked_y_at_x(&footholds, &groups, foothold.prev, x)
+                {
+                    info!("fh({}): previous({})", foothold.id, prev);
+                    commands.entity(entity).insert(prev);
                     position_limit_ground_y(&mut (next_transform.translation), height, y);
-                } else if let Some(y) = container_get_y_at_x(
-                    &footholds_container,
-                    foothold.next,
-                    next_transform.translation.x,
-                ) {
-                    info!("fh({}): next({})", foothold.id, foothold.next);
-                    commands.entity(entity).insert(FootholdId(foothold.next));
+                } else if let Some((next, y)) = linked_y_at_x(&footholds, &groups, foothold.next, x)
+                {
+                    info!("fh({}): next({})", foothold.id, next);
+                    commands.entity(entity).insert(next);
                     position_limit_ground_y(&mut (next_transform.translation), height, y);
                 } else {
                     info!("fh({}): removed", foothold.id);
@@ -124,15 +138,19 @@ fn player_foothold_collision_system(
 
         // Foothold doesn't exist: check for new collisions
         if use_collision {
-            for foothold in footholds.iter() {
+            for foothold in footholds.query.iter() {
+                if !footholds.interacts_with(foothold.id, &groups) {
+                    continue;
+                }
                 if let Some(collision) = calculate_fh_collision(
-                    &footholds_container,
-                    &foothold,
+                    &footholds,
+                    &groups,
+                    foothold,
                     transform.translation,
                     next_transform.translation,
                     height,
                 ) {
-                    commands.entity(entity).insert(FootholdId(foothold.id));
+                    commands.entity(entity).insert(foothold.id);
                     position_limit_ground_y(&mut (next_transform.translation), height, collision.y);
                     break;
                 }
@@ -153,7 +171,8 @@ fn position_limit_ground_y(position: &mut Vec3, height: f32, y: f32) {
 
 /// Calculate any collisions for a foothold, using the current and next points
 fn calculate_fh_collision(
-    container: &Res<FootholdContainer>,
+    footholds: &Footholds,
+    groups: &CollisionGroups,
     foothold: &Foothold,
     current: Vec3,
     next: Vec3,
@@ -163,9 +182,12 @@ fn calculate_fh_collision(
     let current = Vec3::new(current.x, current.y - height / 2.0, 0.0);
     let next = Vec3::new(next.x, next.y - height / 2.0, 0.0);
 
+    let container = &footholds.container;
+    let current_fh_y = container.y_at(foothold.id, current.x);
+
     // Check current foothold
     if let (Some(current_fh_y), Some(next_fh_y)) =
-        (foothold.get_y_at_x(current.x), foothold.get_y_at_x(next.x))
+        (current_fh_y, container.y_at(foothold.id, next.x))
     {
         if current.y >= current_fh_y && next.y <= next_fh_y {
             info!("added fh({})): current", foothold.id);
@@ -173,22 +195,22 @@ fn calculate_fh_collision(
         }
     }
     // Check current and previous
-    else if let (Some(current_fh_y), Some(next_fh_y)) = (
-        foothold.get_y_at_x(current.x),
-        container_get_y_at_x(container, foothold.prev, next.x),
+    else if let (Some(current_fh_y), Some((prev, next_fh_y))) = (
+        current_fh_y,
+        linked_y_at_x(footholds, groups, foothold.prev, next.x),
     ) {
         if current.y >= current_fh_y && next.y <= next_fh_y {
-            info!("added fh({}): previous({})", foothold.id, foothold.prev);
+            info!("added fh({}): previous({})", foothold.id, prev);
             return Some(Vec2::new(next.x, next_fh_y));
         }
     }
     // Check current and next
-    else if let (Some(current_fh_y), Some(next_fh_y)) = (
-        foothold.get_y_at_x(current.x),
-        container_get_y_at_x(container, foothold.next, next.x),
+    else if let (Some(current_fh_y), Some((next_id, next_fh_y))) = (
+        current_fh_y,
+        linked_y_at_x(footholds, groups, foothold.next, next.x),
     ) {
         if current.y >= current_fh_y && next.y <= next_fh_y {
-            info!("added fh({}): next({})", foothold.id, foothold.next);
+            info!("added fh({}): next({})", foothold.id, next_id);
             return Some(Vec2::new(next.x, next_fh_y));
         }
     }
@@ -196,10 +218,13 @@ fn calculate_fh_collision(
     None
 }
 
-fn container_get_y_at_x(container: &Res<FootholdContainer>, id: u32, x: f32) -> Option<f32> {
-    if let Some(foothold) = container.data.get(&id) {
-        foothold.get_y_at_x(x)
-    } else {
-        None
-    }
+/// Get the y coordinate of a linked foothold, if there is a link the groups interact with
+fn linked_y_at_x(
+    footholds: &Footholds,
+    groups: &CollisionGroups,
+    link: Option<FootholdId>,
+    x: f32,
+) -> Option<(FootholdId, f32)> {
+    let id = link.filter(|id| footholds.interacts_with(*id, groups))?;
+    footholds.container.y_at(id, x).map(|y| (id, y))
 }
@@ -0,0 +1,78 @@
+use glam::Vec2;
+
+/// Represents an upright capsule: a vertical segment inflated by a radius
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capsule2d {
+    pub position: Vec2,
+    pub height: f32,
+    pub radius: f32,
+}
+
+impl Capsule2d {
+    /// Creates a new capsule centered at (x, y), with a total height including both caps
+    pub fn new(x: f32, y: f32, height: f32, radius: f32) -> Self {
+        Self {
+            position: Vec2::new(x, y),
+            height,
+            radius,
+        }
+    }
+
+    /// Gets the center of the top cap
+    pub fn top(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y + self.half_segment())
+    }
+
+    /// Gets the center of the bottom cap
+    pub fn bottom(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y - self.half_segment())
+    }
+
+    pub fn mid_top(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y + (self.height / 2.0))
+    }
+
+    pub fn mid_bottom(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y - (self.height / 2.0))
+    }
+
+    /// Gets the bounding box as (min, max)
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let extent = Vec2::new(self.radius, (self.height / 2.0).max(self.radius));
+        (self.position - extent, self.position + extent)
+    }
+
+    /// Half the length of the inner segment, between the centers of the caps
+    fn half_segment(&self) -> f32 {
+        ((self.height / 2.0) - self.radius).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_and_bounds() {
+        let capsule = Capsule2d::new(0.0, 0.0, 10.0, 2.0);
+        assert_eq!(capsule.top(), Vec2::new(0.0, 3.0));
+        assert_eq!(capsule.bottom(), Vec2::new(0.0, -3.0));
+        assert_eq!(capsule.mid_top(), Vec2::new(0.0, 5.0));
+        assert_eq!(capsule.mid_bottom(), Vec2::new(0.0, -5.0));
+        assert_eq!(
+            capsule.bounds(),
+            (Vec2::new(-2.0, -5.0), Vec2::new(2.0, 5.0))
+        );
+    }
+
+    #[test]
+    fn shorter_than_its_caps_is_a_circle() {
+        let capsule = Capsule2d::new(0.0, 0.0, 2.0, 3.0);
+        assert_eq!(capsule.top(), capsule.position);
+        assert_eq!(capsule.bottom(), capsule.position);
+        assert_eq!(
+            capsule.bounds(),
+            (Vec2::new(-3.0, -3.0), Vec2::new(3.0, 3.0))
+        );
+    }
+}
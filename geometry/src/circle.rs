@@ -0,0 +1,47 @@
+use glam::Vec2;
+
+/// Represents a circle with a center and radius
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle2d {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+impl Circle2d {
+    pub fn new(x: f32, y: f32, radius: f32) -> Self {
+        Self {
+            position: Vec2::new(x, y),
+            radius,
+        }
+    }
+
+    pub fn mid_bottom(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y - self.radius)
+    }
+
+    pub fn mid_top(&self) -> Vec2 {
+        Vec2::new(self.position.x, self.position.y + self.radius)
+    }
+
+    /// Gets the bounding box as (min, max)
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let extent = Vec2::splat(self.radius);
+        (self.position - extent, self.position + extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_and_bounds() {
+        let circle = Circle2d::new(1.0, 2.0, 3.0);
+        assert_eq!(circle.mid_bottom(), Vec2::new(1.0, -1.0));
+        assert_eq!(circle.mid_top(), Vec2::new(1.0, 5.0));
+        assert_eq!(
+            circle.bounds(),
+            (Vec2::new(-2.0, -1.0), Vec2::new(4.0, 5.0))
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use glam::Vec2;
+
+use crate::{intersection::*, EPSILON};
+
+/// Represents the closest point on a segment to another point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentProjection {
+    /// Closest point on the segment.
+    pub point: Vec2,
+    /// Position of the point along the segment, from 0.0 at the start to 1.0 at the end.
+    pub t: f32,
+}
+
+/// Calculates the closest point on a segment to a point
+/// Input:
+///   Segment: p1 to p2
+///   Point: point
+/// Output:
+///   The closest point and its position along the segment
+pub fn closest_point_on_segment(p1: Vec2, p2: Vec2, point: Vec2) -> SegmentProjection {
+    let dir = p2 - p1;
+    let length_squared = dir.length_squared();
+
+    // Degenerate segments are points
+    if length_squared < EPSILON * EPSILON {
+        return SegmentProjection { point: p1, t: 0.0 };
+    }
+
+    let t = ((point - p1).dot(dir) / length_squared).clamp(0.0, 1.0);
+    SegmentProjection {
+        point: p1 + dir * t,
+        t,
+    }
+}
+
+/// Calculates the distance between a point and a segment
+/// Input:
+///   Segment: p1 to p2
+///   Point: point
+pub fn distance_point_segment(p1: Vec2, p2: Vec2, point: Vec2) -> f32 {
+    closest_point_on_segment(p1, p2, point)
+        .point
+        .distance(point)
+}
+
+/// Calculates the distance between 2 segments
+/// Input:
+///   Segment A: p1 to p2
+///   Segment B: p3 to p4
+/// Output:
+///   0.0 if the segments touch
+pub fn distance_segment_segment(p1: Vec2, p2: Vec2, p3: Vec2, p4: Vec2) -> f32 {
+    if collide_segment_segment(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y, p4.x, p4.y)
+        != SegmentIntersection::None
+    {
+        return 0.0;
+    }
+
+    // Segments that do not touch are closest at one of the endpoints
+    distance_point_segment(p3, p4, p1)
+        .min(distance_point_segment(p3, p4, p2))
+        .min(distance_point_segment(p1, p2, p3))
+        .min(distance_point_segment(p1, p2, p4))
+}
+
+/// Calculates the closest points between 2 segments
+/// Input:
+///   Segment 1: p1 to q1
+///   Segment 2: p2 to q2
+/// Output:
+///   (Point on segment 1, Point on segment 2)
+pub fn closest_points_segment_segment(p1: Vec2, q1: Vec2, p2: Vec2, q2: Vec2) -> (Vec2, Vec2) {
+    // Crossing segments touch at their intersection
+    if let SegmentIntersection::Point(point) =
+        collide_segment_segment(p1.x, p1.y, q1.x, q1.y, p2.x, p2.y, q2.x, q2.y)
+    {
+        return (point, point);
+    }
+
+    // Otherwise one of the 4 endpoints is part of the closest pair
+    let candidates = [
+        (p1, closest_point_on_segment(p2, q2, p1).point),
+        (q1, closest_point_on_segment(p2, q2, q1).point),
+        (closest_point_on_segment(p1, q1, p2).point, p2),
+        (closest_point_on_segment(p1, q1, q2).point, q2),
+    ];
+    candidates
+        .iter()
+        .copied()
+        .fold(candidates[0], |closest, pair| {
+            if pair.0.distance_squared(pair.1) < closest.0.distance_squared(closest.1) {
+                pair
+            } else {
+                closest
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_point_inside_segment() {
+        let projection = closest_point_on_segment(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(4.0, 3.0),
+        );
+        assert_eq!(projection.point, Vec2::new(4.0, 0.0));
+        assert!((projection.t - 0.4).abs() < EPSILON);
+    }
+
+    #[test]
+    fn closest_point_clamped_to_ends() {
+        let (p1, p2) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0));
+
+        let before = closest_point_on_segment(p1, p2, Vec2::new(-5.0, 2.0));
+        assert_eq!(before, SegmentProjection { point: p1, t: 0.0 });
+
+        let after = closest_point_on_segment(p1, p2, Vec2::new(15.0, -2.0));
+        assert_eq!(after, SegmentProjection { point: p2, t: 1.0 });
+    }
+
+    #[test]
+    fn closest_point_on_zero_length_segment() {
+        let point = Vec2::new(3.0, 3.0);
+        let projection = closest_point_on_segment(point, point, Vec2::new(5.0, 5.0));
+        assert_eq!(projection, SegmentProjection { point, t: 0.0 });
+    }
+
+    #[test]
+    fn point_segment_distance() {
+        let (p1, p2) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0));
+        assert!((distance_point_segment(p1, p2, Vec2::new(4.0, 3.0)) - 3.0).abs() < EPSILON);
+        assert!((distance_point_segment(p1, p2, Vec2::new(13.0, 4.0)) - 5.0).abs() < EPSILON);
+        assert_eq!(distance_point_segment(p1, p2, Vec2::new(6.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn segment_segment_distance() {
+        let (p1, p2) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0));
+
+        // Crossing
+        let distance = distance_segment_segment(p1, p2, Vec2::new(5.0, -5.0), Vec2::new(5.0, 5.0));
+        assert_eq!(distance, 0.0);
+
+        // Parallel
+        let distance = distance_segment_segment(p1, p2, Vec2::new(0.0, 2.0), Vec2::new(10.0, 2.0));
+        assert!((distance - 2.0).abs() < EPSILON);
+
+        // Closest at the endpoints
+        let distance =
+            distance_segment_segment(p1, p2, Vec2::new(13.0, 4.0), Vec2::new(13.0, 10.0));
+        assert!((distance - 5.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn closest_points_between_segments() {
+        let (p1, q1) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0));
+
+        let (a, b) =
+            closest_points_segment_segment(p1, q1, Vec2::new(5.0, -5.0), Vec2::new(5.0, 5.0));
+        assert_eq!(a, b);
+        assert!(a.distance(Vec2::new(5.0, 0.0)) < EPSILON);
+
+        let (a, b) =
+            closest_points_segment_segment(p1, q1, Vec2::new(5.0, 3.0), Vec2::new(5.0, 8.0));
+        assert_eq!((a, b), (Vec2::new(5.0, 0.0), Vec2::new(5.0, 3.0)));
+    }
+}
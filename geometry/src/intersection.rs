@@ -0,0 +1,778 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use crate::{
+    capsule::*, circle::*, distance::*, line::*, polygon::*, quad::*, EPSILON, FLOOR_NORMAL_Y,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Collision {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Collision {
+    /// Gets the direction a quad is pushed out of a line touching this side.
+    pub fn push_direction(&self) -> Vec2 {
+        match self {
+            Collision::Top => -Vec2::Y,
+            Collision::Bottom => Vec2::Y,
+            Collision::Left => Vec2::X,
+            Collision::Right => -Vec2::X,
+        }
+    }
+}
+
+/// Represents the intersection of 2 segments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentIntersection {
+    None,
+    Point(Vec2),
+    Overlap(Line2d),
+}
+
+impl SegmentIntersection {
+    /// Gets a single point representing the intersection.
+    /// Overlapping segments are represented by the middle of the shared segment.
+    pub fn point(&self) -> Option<Vec2> {
+        match self {
+            SegmentIntersection::None => None,
+            SegmentIntersection::Point(point) => Some(*point),
+            SegmentIntersection::Overlap(line) => Some((line.p1 + line.p2) / 2.0),
+        }
+    }
+}
+
+/// Represents a contact between a quad and a line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    /// Contact points, by the side of the quad they are on.
+    pub points: HashMap<Collision, Vec2>,
+    /// Unit normal pointing from the line to the quad.
+    pub normal: Vec2,
+    /// Distance to move the quad along the normal to separate it from the line.
+    pub depth: f32,
+}
+
+impl Contact {
+    /// Gets the sides of the quad that touch the line.
+    pub fn sides(&self) -> impl Iterator<Item = Collision> + '_ {
+        self.points.keys().copied()
+    }
+
+    /// Gets the normal used for resolving this contact.
+    /// The normal must point out of a side that touches the line, so a line poking
+    /// through the bottom pushes the quad up or sideways, never down through the line.
+    /// Returns None if the normal points into every side that touches.
+    pub fn response_normal(&self) -> Option<Vec2> {
+        if self
+            .sides()
+            .any(|side| self.normal.dot(side.push_direction()) > 0.0)
+        {
+            Some(self.normal)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the line is below the quad.
+    pub fn is_floor(&self) -> bool {
+        self.normal.y >= FLOOR_NORMAL_Y
+    }
+
+    /// Returns true if the line is above the quad.
+    pub fn is_ceiling(&self) -> bool {
+        self.normal.y <= -FLOOR_NORMAL_Y
+    }
+
+    /// Returns true if the line is beside the quad.
+    pub fn is_wall(&self) -> bool {
+        !self.is_floor() && !self.is_ceiling()
+    }
+}
+
+/// Calculates the contact for a quad and line
+pub fn collide_quad_line(quad: &Quad2d, line: &Line2d) -> Option<Contact> {
+    let mut collisions = HashMap::new();
+
+    if let Some(point) = collide_line_line(
+        &Line2d::from_points(quad.top_left(), quad.top_right()),
+        line,
+    )
+    .point()
+    {
+        collisions.insert(Collision::Top, point);
+    }
+
+    if let Some(point) = collide_line_line(
+        &Line2d::from_points(quad.bottom_left(), quad.bottom_right()),
+        line,
+    )
+    .point()
+    {
+        collisions.insert(Collision::Bottom, point);
+    }
+
+    if let Some(point) = collide_line_line(
+        &Line2d::from_points(quad.top_left(), quad.bottom_left()),
+        line,
+    )
+    .point()
+    {
+        collisions.insert(Collision::Left, point);
+    }
+
+    if let Some(point) = collide_line_line(
+        &Line2d::from_points(quad.top_right(), quad.bottom_right()),
+        line,
+    )
+    .point()
+    {
+        collisions.insert(Collision::Right, point);
+    }
+
+    if collisions.is_empty() {
+        return None;
+    }
+
+    let (normal, depth) = separate_quad_line(quad, line);
+    Some(Contact {
+        points: collisions,
+        normal,
+        depth,
+    })
+}
+
+/// Calculates the minimum translation separating a quad from a line, by testing
+/// the rotated quad axes and the line normal
+/// Output:
+///   Normal: Unit axis pointing from the line to the quad
+///   Depth: Distance to move the quad along the normal, 0.0 if only touching
+pub fn separate_quad_line(quad: &Quad2d, line: &Line2d) -> (Vec2, f32) {
+    let corners = [
+        quad.top_left(),
+        quad.top_right(),
+        quad.bottom_right(),
+        quad.bottom_left(),
+    ];
+
+    let (right, up) = quad.axes();
+    let mut axes = vec![right, up];
+    if line.p1.distance(line.p2) >= EPSILON {
+        axes.push((line.p2 - line.p1).perp().normalize());
+    }
+
+    let (normal, overlap) = least_overlap(&corners, &[line.p1, line.p2], &axes);
+    (normal, overlap.max(0.0))
+}
+
+/// Calculates the minimum translation vector that moves polygon A out of polygon B,
+/// using the Separating Axis Theorem
+/// Output:
+///   Some: The polygons overlap or touch
+///   None: The polygons are separated
+pub fn collide_polygon_polygon(a: &ConvexPolygon2d, b: &ConvexPolygon2d) -> Option<Vec2> {
+    let mut axes = a.normals();
+    axes.extend(b.normals());
+
+    let (normal, overlap) = least_overlap(&a.world_points(), &b.world_points(), &axes);
+    if overlap < -EPSILON {
+        None
+    } else {
+        Some(normal * overlap.max(0.0))
+    }
+}
+
+/// Calculates the minimum translation vector that moves a polygon out of a line,
+/// using the Separating Axis Theorem
+/// Output:
+///   Some: The polygon overlaps or touches the line
+///   None: The polygon and line are separated
+pub fn collide_polygon_line(polygon: &ConvexPolygon2d, line: &Line2d) -> Option<Vec2> {
+    let mut axes = polygon.normals();
+    if line.p1.distance(line.p2) >= EPSILON {
+        axes.push((line.p2 - line.p1).perp().normalize());
+    }
+
+    let (normal, overlap) = least_overlap(&polygon.world_points(), &[line.p1, line.p2], &axes);
+    if overlap < -EPSILON {
+        None
+    } else {
+        Some(normal * overlap.max(0.0))
+    }
+}
+
+/// Finds the axis with the least overlap between the projections of 2 sets of points
+/// Output:
+///   Normal: Unit axis pointing from B to A
+///   Overlap: Overlap along the axis, negative if the sets are separated on it
+fn least_overlap(a: &[Vec2], b: &[Vec2], axes: &[Vec2]) -> (Vec2, f32) {
+    let center_a = a.iter().sum::<Vec2>() / a.len() as f32;
+    let center_b = b.iter().sum::<Vec2>() / b.len() as f32;
+
+    let mut normal = Vec2::Y;
+    let mut least = f32::MAX;
+    for axis in axes {
+        let (a_min, a_max) = project_points(a, *axis);
+        let (b_min, b_max) = project_points(b, *axis);
+
+        let overlap = (a_max - b_min).min(b_max - a_min);
+        if overlap < least {
+            least = overlap;
+            normal = if center_a.dot(*axis) >= center_b.dot(*axis) {
+                *axis
+            } else {
+                -*axis
+            };
+        }
+    }
+
+    (normal, least)
+}
+
+/// Projects points onto an axis, returning the min and max values
+fn project_points(points: &[Vec2], axis: Vec2) -> (f32, f32) {
+    points
+        .iter()
+        .map(|point| point.dot(axis))
+        .fold((f32::MAX, f32::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+/// Calculates the intersection for 2 lines
+pub fn collide_line_line(line_a: &Line2d, line_b: &Line2d) -> SegmentIntersection {
+    collide_segment_segment(
+        line_a.p1.x,
+        line_a.p1.y,
+        line_a.p2.x,
+        line_a.p2.y,
+        line_b.p1.x,
+        line_b.p1.y,
+        line_b.p2.x,
+        line_b.p2.y,
+    )
+}
+
+/// Calculates the intersection for 2 lines by their points
+/// Input:
+///   Line A: (x1, y1) to (x2, y2)
+///   Line B: (x3, y3) to (x4, y4)
+/// Output:
+///   None: The segments do not touch
+///   Point: The segments cross or touch at (x, y)
+///   Overlap: The segments are collinear and share a segment
+#[allow(clippy::too_many_arguments)]
+pub fn collide_segment_segment(
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    x3: f32,
+    y3: f32,
+    x4: f32,
+    y4: f32,
+) -> SegmentIntersection {
+    let p = Vec2::new(x1, y1);
+    let q = Vec2::new(x3, y3);
+    let r = Vec2::new(x2 - x1, y2 - y1);
+    let s = Vec2::new(x4 - x3, y4 - y3);
+    let qp = q - p;
+
+    let r_len = r.length();
+    let s_len = s.length();
+
+    // Degenerate segments are points
+    if r_len < EPSILON && s_len < EPSILON {
+        return if p.distance(q) < EPSILON {
+            SegmentIntersection::Point(p)
+        } else {
+            SegmentIntersection::None
+        };
+    }
+    if r_len < EPSILON {
+        return intersect_point_segment(p, q, s);
+    }
+    if s_len < EPSILON {
+        return intersect_point_segment(q, p, r);
+    }
+
+    let denom = r.perp_dot(s);
+
+    // Parallel segments: the sine of the angle between them is within tolerance
+    if denom.abs() <= EPSILON * r_len * s_len {
+        // Line B is not on line A
+        if qp.perp_dot(r).abs() > EPSILON * r_len {
+            return SegmentIntersection::None;
+        }
+
+        // Collinear: project line B onto line A and clip it to line A
+        let rr = r.dot(r);
+        let t0 = qp.dot(r) / rr;
+        let t1 = t0 + s.dot(r) / rr;
+        let lo = t0.min(t1).max(0.0);
+        let hi = t0.max(t1).min(1.0);
+
+        let tolerance = EPSILON / r_len;
+        return if lo > hi + tolerance {
+            SegmentIntersection::None
+        } else if hi - lo <= tolerance {
+            SegmentIntersection::Point(p + r * ((lo + hi) / 2.0))
+        } else {
+            SegmentIntersection::Overlap(Line2d::from_points(p + r * lo, p + r * hi))
+        };
+    }
+
+    // calculate the distance to intersection point
+    let u_a = qp.perp_dot(s) / denom;
+    let u_b = qp.perp_dot(r) / denom;
+
+    // if u_a and u_b are between 0.0 and 1.0 (within tolerance), lines are colliding
+    let tolerance_a = EPSILON / r_len;
+    let tolerance_b = EPSILON / s_len;
+    if u_a >= -tolerance_a
+        && u_a <= 1.0 + tolerance_a
+        && u_b >= -tolerance_b
+        && u_b <= 1.0 + tolerance_b
+    {
+        SegmentIntersection::Point(p + r * u_a.clamp(0.0, 1.0))
+    } else {
+        SegmentIntersection::None
+    }
+}
+
+/// Calculates the intersection for a point and the segment from `origin` to `origin + dir`
+fn intersect_point_segment(point: Vec2, origin: Vec2, dir: Vec2) -> SegmentIntersection {
+    let t = ((point - origin).dot(dir) / dir.dot(dir)).clamp(0.0, 1.0);
+    if (origin + dir * t).distance(point) < EPSILON {
+        SegmentIntersection::Point(point)
+    } else {
+        SegmentIntersection::None
+    }
+}
+
+/// Maximum distance between a point and a segment for the point to lie on it.
+pub const POINT_ON_SEGMENT_TOLERANCE: f32 = 0.5;
+
+/// Calculates whether a point lies on a segment
+/// Input:
+///   Line: (x1, y1) to (x2, y2)
+///   Point: (x, y)
+/// Output:
+///   Some: The position of the point along the segment, from 0.0 to 1.0
+///   None: The point is further than `POINT_ON_SEGMENT_TOLERANCE` from the segment
+pub fn collide_segment_point(x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) -> Option<f32> {
+    let point = Vec2::new(x, y);
+    let projection = closest_point_on_segment(Vec2::new(x1, y1), Vec2::new(x2, y2), point);
+    if projection.point.distance(point) <= POINT_ON_SEGMENT_TOLERANCE {
+        Some(projection.t)
+    } else {
+        None
+    }
+}
+
+/// Calculates the minimum translation vector that moves one round shape out of another.
+/// Round shapes are segments inflated by a radius, a circle being a segment of length 0.
+/// Input:
+///   A: The closest point on the core of A, with its radius
+///   B: The closest point on the core of B, with its radius
+///   Fallback: Normal used when the cores touch
+fn separate_round(a: Vec2, radius_a: f32, b: Vec2, radius_b: f32, fallback: Vec2) -> Option<Vec2> {
+    let offset = a - b;
+    let distance = offset.length();
+    let overlap = radius_a + radius_b - distance;
+    if overlap < -EPSILON {
+        return None;
+    }
+
+    let normal = if distance < EPSILON {
+        fallback
+    } else {
+        offset / distance
+    };
+    Some(normal * overlap.max(0.0))
+}
+
+/// Gets the unit normal of a line facing `point`, or up when the line is degenerate
+fn line_normal_facing(line: &Line2d, point: Vec2) -> Vec2 {
+    let s = line.p2 - line.p1;
+    if s.length() < EPSILON {
+        return Vec2::Y;
+    }
+    let normal = s.perp().normalize();
+    if (point - line.p1).dot(normal) >= 0.0 {
+        normal
+    } else {
+        -normal
+    }
+}
+
+/// Calculates the minimum translation vector that moves circle A out of circle B
+/// Output:
+///   Some: The circles overlap or touch
+///   None: The circles are separated
+pub fn collide_circle_circle(a: &Circle2d, b: &Circle2d) -> Option<Vec2> {
+    separate_round(a.position, a.radius, b.position, b.radius, Vec2::Y)
+}
+
+/// Calculates the minimum translation vector that moves a circle out of a line
+/// Output:
+///   Some: The circle overlaps or touches the line
+///   None: The circle and line are separated
+pub fn collide_circle_line(circle: &Circle2d, line: &Line2d) -> Option<Vec2> {
+    let closest = closest_point_on_segment(line.p1, line.p2, circle.position).point;
+    let fallback = line_normal_facing(line, circle.position);
+    separate_round(circle.position, circle.radius, closest, 0.0, fallback)
+}
+
+/// Calculates the minimum translation vector that moves a circle out of a capsule
+/// Output:
+///   Some: The circle overlaps or touches the capsule
+///   None: The circle and capsule are separated
+pub fn collide_circle_capsule(circle: &Circle2d, capsule: &Capsule2d) -> Option<Vec2> {
+    let closest = closest_point_on_segment(capsule.bottom(), capsule.top(), circle.position).point;
+    separate_round(
+        circle.position,
+        circle.radius,
+        closest,
+        capsule.radius,
+        Vec2::X,
+    )
+}
+
+/// Calculates the minimum translation vector that moves capsule A out of capsule B
+/// Output:
+///   Some: The capsules overlap or touch
+///   None: The capsules are separated
+pub fn collide_capsule_capsule(a: &Capsule2d, b: &Capsule2d) -> Option<Vec2> {
+    let (closest_a, closest_b) =
+        closest_points_segment_segment(a.bottom(), a.top(), b.bottom(), b.top());
+    let fallback = if a.position.x >= b.position.x {
+        Vec2::X
+    } else {
+        -Vec2::X
+    };
+    separate_round(closest_a, a.radius, closest_b, b.radius, fallback)
+}
+
+/// Calculates the minimum translation vector that moves a capsule out of a line
+/// Output:
+///   Some: The capsule overlaps or touches the line
+///   None: The capsule and line are separated
+pub fn collide_capsule_line(capsule: &Capsule2d, line: &Line2d) -> Option<Vec2> {
+    let (bottom, top) = (capsule.bottom(), capsule.top());
+    let (closest_capsule, closest_line) =
+        closest_points_segment_segment(bottom, top, line.p1, line.p2);
+    if closest_capsule.distance(closest_line) >= EPSILON {
+        return separate_round(closest_capsule, capsule.radius, closest_line, 0.0, Vec2::Y);
+    }
+
+    // The core crosses the line: push the deepest end back to the side of the center
+    let normal = line_normal_facing(line, capsule.position);
+    let depth = [bottom, top]
+        .iter()
+        .map(|end| -(*end - line.p1).dot(normal))
+        .fold(0.0_f32, f32::max);
+    Some(normal * (depth + capsule.radius))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn assert_point(intersection: SegmentIntersection, expected: Vec2) {
+        match intersection {
+            SegmentIntersection::Point(point) => {
+                assert!(
+                    point.distance(expected) < EPSILON,
+                    "{:?} != {:?}",
+                    point,
+                    expected
+                )
+            }
+            other => panic!("expected a point at {:?}, got {:?}", expected, other),
+        }
+    }
+
+    fn assert_overlap(intersection: SegmentIntersection, p1: Vec2, p2: Vec2) {
+        match intersection {
+            SegmentIntersection::Overlap(line) => {
+                assert!(line.p1.distance(p1) < EPSILON, "{:?} != {:?}", line.p1, p1);
+                assert!(line.p2.distance(p2) < EPSILON, "{:?} != {:?}", line.p2, p2);
+            }
+            other => panic!("expected an overlap, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn segments_crossing() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 10.0, 0.0, 10.0, 10.0, 0.0);
+        assert_point(intersection, Vec2::new(5.0, 5.0));
+    }
+
+    #[test]
+    fn segments_not_reaching() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 5.0, 1.0, 5.0, 10.0);
+        assert_eq!(intersection, SegmentIntersection::None);
+    }
+
+    #[test]
+    fn segments_parallel() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 0.0, 5.0, 10.0, 5.0);
+        assert_eq!(intersection, SegmentIntersection::None);
+    }
+
+    #[test]
+    fn segments_collinear_overlap() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 5.0, 0.0, 15.0, 0.0);
+        assert_overlap(intersection, Vec2::new(5.0, 0.0), Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn segments_collinear_overlap_opposite_direction() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 15.0, 0.0, 5.0, 0.0);
+        assert_overlap(intersection, Vec2::new(5.0, 0.0), Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn segments_collinear_contained() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 10.0, 2.0, 2.0, 4.0, 4.0);
+        assert_overlap(intersection, Vec2::new(2.0, 2.0), Vec2::new(4.0, 4.0));
+    }
+
+    #[test]
+    fn segments_collinear_disjoint() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 11.0, 0.0, 20.0, 0.0);
+        assert_eq!(intersection, SegmentIntersection::None);
+    }
+
+    #[test]
+    fn segments_collinear_touching_ends() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 10.0, 0.0, 20.0, 0.0);
+        assert_point(intersection, Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn segments_shared_endpoint() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 10.0, 0.0, 10.0, 10.0);
+        assert_point(intersection, Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn segments_endpoint_on_segment() {
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 5.0, 0.0, 5.0, 10.0);
+        assert_point(intersection, Vec2::new(5.0, 0.0));
+    }
+
+    #[test]
+    fn segments_zero_length_on_segment() {
+        let intersection = collide_segment_segment(5.0, 0.0, 5.0, 0.0, 0.0, 0.0, 10.0, 0.0);
+        assert_point(intersection, Vec2::new(5.0, 0.0));
+
+        let intersection = collide_segment_segment(0.0, 0.0, 10.0, 0.0, 5.0, 0.0, 5.0, 0.0);
+        assert_point(intersection, Vec2::new(5.0, 0.0));
+    }
+
+    #[test]
+    fn segments_zero_length_off_segment() {
+        let intersection = collide_segment_segment(5.0, 1.0, 5.0, 1.0, 0.0, 0.0, 10.0, 0.0);
+        assert_eq!(intersection, SegmentIntersection::None);
+    }
+
+    #[test]
+    fn segments_both_zero_length() {
+        let intersection = collide_segment_segment(1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0);
+        assert_point(intersection, Vec2::new(1.0, 1.0));
+
+        let intersection = collide_segment_segment(1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0);
+        assert_eq!(intersection, SegmentIntersection::None);
+    }
+
+    #[test]
+    fn segments_near_parallel_within_epsilon() {
+        // The angle between both segments is below EPSILON, so they are treated as parallel
+        let intersection = collide_segment_segment(0.0, 0.0, 100.0, 0.0, 0.0, 0.01, 100.0, 0.05);
+        assert_eq!(intersection, SegmentIntersection::None);
+
+        // Close enough to line A to be collinear
+        let intersection =
+            collide_segment_segment(0.0, 0.0, 100.0, 0.0, 50.0, 0.0005, 150.0, 0.0505);
+        assert_overlap(intersection, Vec2::new(50.0, 0.0), Vec2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn segments_near_parallel_outside_epsilon() {
+        let intersection = collide_segment_segment(0.0, 0.0, 100.0, 0.0, 0.0, -1.0, 100.0, 1.0);
+        assert_point(intersection, Vec2::new(50.0, 0.0));
+    }
+
+    #[test]
+    fn segment_intersection_point_of_overlap() {
+        let overlap = SegmentIntersection::Overlap(Line2d::new(0.0, 0.0, 10.0, 0.0));
+        assert_eq!(overlap.point(), Some(Vec2::new(5.0, 0.0)));
+        assert_eq!(SegmentIntersection::None.point(), None);
+    }
+
+    #[test]
+    fn quad_line_contact() {
+        let quad = Quad2d::new(0.0, 4.0, 10.0, 10.0);
+        let line = Line2d::new(-10.0, 0.0, 10.0, 0.0);
+
+        let contact = collide_quad_line(&quad, &line).unwrap();
+        assert_eq!(
+            contact.points.get(&Collision::Left),
+            Some(&Vec2::new(-5.0, 0.0))
+        );
+        assert_eq!(
+            contact.points.get(&Collision::Right),
+            Some(&Vec2::new(5.0, 0.0))
+        );
+        assert!(!contact.points.contains_key(&Collision::Bottom));
+        assert_eq!(contact.normal, Vec2::Y);
+        assert!((contact.depth - 1.0).abs() < EPSILON);
+        assert!(contact.is_floor());
+
+        let above = Quad2d::new(0.0, 10.0, 10.0, 10.0);
+        assert!(collide_quad_line(&above, &line).is_none());
+    }
+
+    #[test]
+    fn contact_response_normal() {
+        let contact = |side: Collision, normal: Vec2| Contact {
+            points: [(side, Vec2::ZERO)].into_iter().collect(),
+            normal,
+            depth: 1.0,
+        };
+
+        // Pushed out of the side that touches
+        let floor = contact(Collision::Bottom, Vec2::Y);
+        assert_eq!(floor.response_normal(), Some(Vec2::Y));
+        let wall = contact(Collision::Left, Vec2::X);
+        assert_eq!(wall.response_normal(), Some(Vec2::X));
+
+        // Never pushed through the line into the side that touches
+        assert_eq!(contact(Collision::Top, Vec2::Y).response_normal(), None);
+        assert_eq!(contact(Collision::Right, Vec2::X).response_normal(), None);
+        assert_eq!(contact(Collision::Left, Vec2::Y).response_normal(), None);
+    }
+
+    #[test]
+    fn polygon_polygon_overlap() {
+        let a = ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 10.0, 10.0));
+        let b = ConvexPolygon2d::from(&Quad2d::new(8.0, 0.0, 10.0, 10.0));
+
+        let mtv = collide_polygon_polygon(&a, &b).unwrap();
+        assert!(mtv.distance(Vec2::new(-2.0, 0.0)) < EPSILON);
+    }
+
+    #[test]
+    fn polygon_polygon_touching_and_separated() {
+        let a = ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 10.0, 10.0));
+        let touching = ConvexPolygon2d::from(&Quad2d::new(10.0, 0.0, 10.0, 10.0));
+        let separated = ConvexPolygon2d::from(&Quad2d::new(11.0, 0.0, 10.0, 10.0));
+
+        assert_eq!(collide_polygon_polygon(&a, &touching), Some(Vec2::ZERO));
+        assert_eq!(collide_polygon_polygon(&a, &separated), None);
+    }
+
+    #[test]
+    fn polygon_polygon_rotated_separating_axis() {
+        // The bounding boxes overlap, but an edge of the diamond separates them
+        let diamond =
+            ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 10.0, 10.0).with_rotation(FRAC_PI_4));
+        let corner = ConvexPolygon2d::from(&Quad2d::new(6.0, 6.0, 2.0, 2.0));
+
+        assert_eq!(collide_polygon_polygon(&diamond, &corner), None);
+        assert_eq!(collide_polygon_polygon(&corner, &diamond), None);
+    }
+
+    #[test]
+    fn polygon_line_overlap() {
+        let polygon = ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 10.0, 10.0));
+
+        let mtv = collide_polygon_line(&polygon, &Line2d::new(-10.0, 4.0, 10.0, 4.0)).unwrap();
+        assert!(mtv.distance(Vec2::new(0.0, -1.0)) < EPSILON);
+
+        let line = Line2d::new(-10.0, 6.0, 10.0, 6.0);
+        assert_eq!(collide_polygon_line(&polygon, &line), None);
+    }
+
+    #[test]
+    fn circle_circle_overlap() {
+        let a = Circle2d::new(0.0, 0.0, 2.0);
+
+        let mtv = collide_circle_circle(&a, &Circle2d::new(3.0, 0.0, 2.0)).unwrap();
+        assert!(mtv.distance(Vec2::new(-1.0, 0.0)) < EPSILON);
+        assert_eq!(
+            collide_circle_circle(&a, &Circle2d::new(5.0, 0.0, 2.0)),
+            None
+        );
+
+        // Same center, pushed up
+        let mtv = collide_circle_circle(&a, &Circle2d::new(0.0, 0.0, 2.0)).unwrap();
+        assert!(mtv.distance(Vec2::new(0.0, 4.0)) < EPSILON);
+    }
+
+    #[test]
+    fn circle_line_overlap() {
+        let line = Line2d::new(-10.0, 0.0, 10.0, 0.0);
+
+        let mtv = collide_circle_line(&Circle2d::new(0.0, 1.0, 2.0), &line).unwrap();
+        assert!(mtv.distance(Vec2::new(0.0, 1.0)) < EPSILON);
+        assert_eq!(
+            collide_circle_line(&Circle2d::new(0.0, 3.0, 2.0), &line),
+            None
+        );
+    }
+
+    #[test]
+    fn circle_capsule_overlap() {
+        let capsule = Capsule2d::new(0.0, 0.0, 10.0, 3.0);
+
+        let mtv = collide_circle_capsule(&Circle2d::new(3.0, 0.0, 1.0), &capsule).unwrap();
+        assert!(mtv.distance(Vec2::new(1.0, 0.0)) < EPSILON);
+
+        // Above the top cap
+        let mtv = collide_circle_capsule(&Circle2d::new(0.0, 5.5, 1.0), &capsule).unwrap();
+        assert!(mtv.distance(Vec2::new(0.0, 0.5)) < EPSILON);
+        assert_eq!(
+            collide_circle_capsule(&Circle2d::new(0.0, 7.0, 1.0), &capsule),
+            None
+        );
+    }
+
+    #[test]
+    fn capsule_capsule_overlap() {
+        let a = Capsule2d::new(3.0, 0.0, 10.0, 2.0);
+
+        let mtv = collide_capsule_capsule(&a, &Capsule2d::new(0.0, 0.0, 10.0, 2.0)).unwrap();
+        assert!(mtv.distance(Vec2::new(1.0, 0.0)) < EPSILON);
+        assert_eq!(
+            collide_capsule_capsule(&a, &Capsule2d::new(-2.0, 0.0, 10.0, 2.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn capsule_line_overlap() {
+        let line = Line2d::new(-10.0, 0.0, 10.0, 0.0);
+
+        // Resting slightly in the line
+        let mtv = collide_capsule_line(&Capsule2d::new(0.0, 4.5, 10.0, 1.0), &line).unwrap();
+        assert!(mtv.distance(Vec2::new(0.0, 0.5)) < EPSILON);
+
+        // The core crosses the line, so the bottom is pushed back above it
+        let mtv = collide_capsule_line(&Capsule2d::new(0.0, 2.0, 10.0, 1.0), &line).unwrap();
+        assert!(mtv.distance(Vec2::new(0.0, 3.0)) < EPSILON);
+
+        assert_eq!(
+            collide_capsule_line(&Capsule2d::new(0.0, 6.0, 10.0, 1.0), &line),
+            None
+        );
+    }
+}
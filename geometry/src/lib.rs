@@ -0,0 +1,31 @@
+//! Shapes, intersection, distance, sweep and foothold surface math in 2d.
+//!
+//! Only depends on glam, so tools that do not run Bevy can share the math with the game.
+//! The Bevy crates wrap these shapes in components.
+
+pub mod capsule;
+pub mod circle;
+pub mod distance;
+pub mod intersection;
+pub mod line;
+pub mod polygon;
+pub mod quad;
+pub mod surface;
+pub mod sweep;
+
+pub use capsule::*;
+pub use circle::*;
+pub use distance::*;
+pub use glam::Vec2;
+pub use intersection::*;
+pub use line::*;
+pub use polygon::*;
+pub use quad::*;
+pub use surface::*;
+pub use sweep::*;
+
+/// Tolerance used for distances when calculating intersections.
+pub const EPSILON: f32 = 0.001;
+
+/// Minimum y of a surface normal for the surface to count as a floor.
+pub const FLOOR_NORMAL_Y: f32 = 0.5;
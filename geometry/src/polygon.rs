@@ -0,0 +1,148 @@
+use glam::Vec2;
+
+use crate::quad::*;
+
+/// Represents a convex polygon with points relative to its position
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvexPolygon2d {
+    pub position: Vec2,
+    pub points: Vec<Vec2>,
+}
+
+impl ConvexPolygon2d {
+    /// Creates a new polygon at (x, y) from points relative to it.
+    /// The points must form a convex shape, and are stored counter-clockwise.
+    pub fn new(x: f32, y: f32, points: &[Vec2]) -> Self {
+        let mut points = points.to_vec();
+
+        // Shoelace formula: a negative area means the points are clockwise
+        let area: f32 = (0..points.len())
+            .map(|it| points[it].perp_dot(points[(it + 1) % points.len()]))
+            .sum();
+        if area < 0.0 {
+            points.reverse();
+        }
+
+        Self {
+            position: Vec2::new(x, y),
+            points,
+        }
+    }
+
+    /// Gets the points in world coordinates
+    pub fn world_points(&self) -> Vec<Vec2> {
+        self.points
+            .iter()
+            .map(|point| self.position + *point)
+            .collect()
+    }
+
+    /// Returns true if the point is inside or on the edge of the polygon
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        // Inside means behind every edge, using the outward normal -perp
+        self.edges()
+            .iter()
+            .all(|(p1, p2)| (point - *p1).dot(-(*p2 - *p1).perp()) <= 0.0)
+    }
+
+    /// Gets the bounding box as (min, max)
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        self.world_points().iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), point| (min.min(*point), max.max(*point)),
+        )
+    }
+
+    /// Gets the edges in world coordinates, as pairs of points
+    pub fn edges(&self) -> Vec<(Vec2, Vec2)> {
+        let points = self.world_points();
+        (0..points.len())
+            .map(|it| (points[it], points[(it + 1) % points.len()]))
+            .collect()
+    }
+
+    /// Gets the outward unit normal of every edge
+    pub fn normals(&self) -> Vec<Vec2> {
+        self.edges()
+            .iter()
+            .filter(|(p1, p2)| p1 != p2)
+            .map(|(p1, p2)| -(*p2 - *p1).perp().normalize())
+            .collect()
+    }
+}
+
+impl From<&Quad2d> for ConvexPolygon2d {
+    fn from(quad: &Quad2d) -> Self {
+        let position = quad.position;
+        ConvexPolygon2d::new(
+            position.x,
+            position.y,
+            &[
+                quad.bottom_left() - position,
+                quad.bottom_right() - position,
+                quad.top_right() - position,
+                quad.top_left() - position,
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square_points() -> [Vec2; 4] {
+        [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn clockwise_points_are_reversed() {
+        let mut clockwise = square_points();
+        clockwise.reverse();
+
+        let polygon = ConvexPolygon2d::new(0.0, 0.0, &clockwise);
+        assert_eq!(polygon.points, square_points().to_vec());
+    }
+
+    #[test]
+    fn contains_point() {
+        let polygon = ConvexPolygon2d::new(5.0, 5.0, &square_points());
+        assert!(polygon.contains_point(Vec2::new(5.5, 4.5)));
+        assert!(polygon.contains_point(Vec2::new(6.0, 6.0)));
+        assert!(!polygon.contains_point(Vec2::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn normals_point_outward() {
+        let polygon = ConvexPolygon2d::new(0.0, 0.0, &square_points());
+        assert_eq!(
+            polygon.normals(),
+            vec![-Vec2::Y, Vec2::X, Vec2::Y, -Vec2::X]
+        );
+    }
+
+    #[test]
+    fn bounds_and_edges() {
+        let polygon = ConvexPolygon2d::new(2.0, 0.0, &square_points());
+        assert_eq!(
+            polygon.bounds(),
+            (Vec2::new(1.0, -1.0), Vec2::new(3.0, 1.0))
+        );
+        assert_eq!(
+            polygon.edges()[0],
+            (Vec2::new(1.0, -1.0), Vec2::new(3.0, -1.0))
+        );
+    }
+
+    #[test]
+    fn from_quad() {
+        let polygon = ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 2.0, 2.0));
+        assert_eq!(polygon.position, Vec2::ZERO);
+        assert_eq!(polygon.points, square_points().to_vec());
+    }
+}
@@ -0,0 +1,287 @@
+use glam::Vec2;
+
+use crate::EPSILON;
+
+/// Represents a rectangle centered at a position, rotated around it
+#[derive(Debug, Clone)]
+pub struct Quad2d {
+    pub position: Vec2,
+    pub width: f32,
+    pub height: f32,
+    /// Counter-clockwise rotation in radians.
+    pub rotation: f32,
+}
+
+impl Quad2d {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            position: Vec2::new(x, y),
+            width,
+            height,
+            rotation: 0.0,
+        }
+    }
+
+    /// Sets the counter-clockwise rotation in radians.
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Gets the unit axes of the quad, as (right, up)
+    pub fn axes(&self) -> (Vec2, Vec2) {
+        let right = Vec2::new(self.rotation.cos(), self.rotation.sin());
+        (right, right.perp())
+    }
+
+    /// Converts a point relative to the unrotated center into world coordinates
+    pub fn to_world(&self, offset: Vec2) -> Vec2 {
+        let (right, up) = self.axes();
+        self.position + (right * offset.x) + (up * offset.y)
+    }
+
+    /// Converts a world point into a point relative to the unrotated center
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        let (right, up) = self.axes();
+        let offset = point - self.position;
+        Vec2::new(offset.dot(right), offset.dot(up))
+    }
+
+    pub fn top_left(&self) -> Vec2 {
+        self.to_world(Vec2::new(-(self.width / 2.0), self.height / 2.0))
+    }
+
+    pub fn bottom_left(&self) -> Vec2 {
+        self.to_world(Vec2::new(-(self.width / 2.0), -(self.height / 2.0)))
+    }
+
+    pub fn top_right(&self) -> Vec2 {
+        self.to_world(Vec2::new(self.width / 2.0, self.height / 2.0))
+    }
+
+    pub fn bottom_right(&self) -> Vec2 {
+        self.to_world(Vec2::new(self.width / 2.0, -(self.height / 2.0)))
+    }
+
+    pub fn mid_top(&self) -> Vec2 {
+        self.to_world(Vec2::new(0.0, self.height / 2.0))
+    }
+
+    pub fn mid_bottom(&self) -> Vec2 {
+        self.to_world(Vec2::new(0.0, -(self.height / 2.0)))
+    }
+
+    pub fn mid_left(&self) -> Vec2 {
+        self.to_world(Vec2::new(-(self.width / 2.0), 0.0))
+    }
+
+    pub fn mid_right(&self) -> Vec2 {
+        self.to_world(Vec2::new(self.width / 2.0, 0.0))
+    }
+
+    /// Gets the bounding box as (min, max), containing the rotated corners
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        [self.top_left(), self.top_right(), self.bottom_right()]
+            .iter()
+            .fold(
+                (self.bottom_left(), self.bottom_left()),
+                |(min, max), corner| (min.min(*corner), max.max(*corner)),
+            )
+    }
+
+    /// Gets the corners counter-clockwise, starting at the bottom left
+    pub fn corners(&self) -> [Vec2; 4] {
+        [
+            self.bottom_left(),
+            self.bottom_right(),
+            self.top_right(),
+            self.top_left(),
+        ]
+    }
+
+    /// Creates an unrotated quad from its min and max corners
+    pub fn from_min_max(min: Vec2, max: Vec2) -> Self {
+        let center = (min + max) / 2.0;
+        Self::new(center.x, center.y, max.x - min.x, max.y - min.y)
+    }
+
+    /// Creates an unrotated quad from any 2 opposite corners
+    pub fn from_corners(a: Vec2, b: Vec2) -> Self {
+        Self::from_min_max(a.min(b), a.max(b))
+    }
+
+    /// Returns true if the quads overlap or touch
+    pub fn intersects(&self, other: &Quad2d) -> bool {
+        // Separating axis theorem: the quads are apart if any edge axis separates them
+        let (right, up) = self.axes();
+        let (other_right, other_up) = other.axes();
+        let corners = self.corners();
+        let other_corners = other.corners();
+
+        [right, up, other_right, other_up].iter().all(|axis| {
+            let (min, max) = project_corners(&corners, *axis);
+            let (other_min, other_max) = project_corners(&other_corners, *axis);
+            min <= other_max + EPSILON && max >= other_min - EPSILON
+        })
+    }
+
+    /// Returns true if the point is inside or on the edge of this quad
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        let local = self.to_local(point);
+        local.x.abs() <= (self.width / 2.0) + EPSILON
+            && local.y.abs() <= (self.height / 2.0) + EPSILON
+    }
+
+    /// Returns true if the other quad is completely inside this quad
+    pub fn contains_quad(&self, other: &Quad2d) -> bool {
+        other
+            .corners()
+            .iter()
+            .all(|corner| self.contains_point(*corner))
+    }
+
+    // The rectangle operations below use the bounding box of rotated quads.
+
+    /// Gets the area shared by both quads, or None if they do not overlap
+    pub fn intersection(&self, other: &Quad2d) -> Option<Quad2d> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let (min, max) = self.bounds();
+        let (other_min, other_max) = other.bounds();
+        Some(Quad2d::from_min_max(min.max(other_min), max.min(other_max)))
+    }
+
+    /// Gets the smallest quad containing both quads
+    pub fn union(&self, other: &Quad2d) -> Quad2d {
+        let (min, max) = self.bounds();
+        let (other_min, other_max) = other.bounds();
+        Quad2d::from_min_max(min.min(other_min), max.max(other_max))
+    }
+
+    /// Gets a quad grown by an amount on every side, or shrunk if the amount is negative
+    pub fn expand(&self, amount: f32) -> Quad2d {
+        let (min, max) = self.bounds();
+        let center = (min + max) / 2.0;
+        let min = (min - Vec2::splat(amount)).min(center);
+        let max = (max + Vec2::splat(amount)).max(center);
+        Quad2d::from_min_max(min, max)
+    }
+}
+
+/// Gets the (min, max) of corners projected onto an axis
+fn project_corners(corners: &[Vec2; 4], axis: Vec2) -> (f32, f32) {
+    corners
+        .iter()
+        .map(|corner| corner.dot(axis))
+        .fold((f32::MAX, f32::MIN), |(min, max), it| {
+            (min.min(it), max.max(it))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use super::*;
+
+    #[test]
+    fn local_and_world_points() {
+        let quad = Quad2d::new(10.0, 0.0, 4.0, 2.0).with_rotation(FRAC_PI_2);
+
+        // The right axis points up once rotated
+        let local = quad.to_local(Vec2::new(10.0, 5.0));
+        assert!(local.distance(Vec2::new(5.0, 0.0)) < EPSILON);
+
+        let world = quad.to_world(Vec2::new(5.0, 0.0));
+        assert!(world.distance(Vec2::new(10.0, 5.0)) < EPSILON);
+        assert!(quad.top_right().distance(Vec2::new(9.0, 2.0)) < EPSILON);
+    }
+
+    #[test]
+    fn bounds_unrotated() {
+        let quad = Quad2d::new(1.0, 2.0, 4.0, 6.0);
+        assert_eq!(quad.bounds(), (Vec2::new(-1.0, -1.0), Vec2::new(3.0, 5.0)));
+    }
+
+    #[test]
+    fn bounds_rotated() {
+        let (min, max) = Quad2d::new(0.0, 0.0, 10.0, 10.0)
+            .with_rotation(FRAC_PI_4)
+            .bounds();
+        let extent = 50.0_f32.sqrt();
+        assert!(min.distance(Vec2::splat(-extent)) < EPSILON);
+        assert!(max.distance(Vec2::splat(extent)) < EPSILON);
+    }
+
+    #[test]
+    fn from_corners_in_any_order() {
+        let quad = Quad2d::from_corners(Vec2::new(4.0, 0.0), Vec2::new(0.0, 2.0));
+        assert_eq!(quad.position, Vec2::new(2.0, 1.0));
+        assert_eq!((quad.width, quad.height), (4.0, 2.0));
+    }
+
+    #[test]
+    fn contains_point_unrotated() {
+        let quad = Quad2d::new(0.0, 0.0, 10.0, 4.0);
+        assert!(quad.contains_point(Vec2::new(4.0, 1.0)));
+        assert!(quad.contains_point(Vec2::new(5.0, 2.0)));
+        assert!(!quad.contains_point(Vec2::new(5.5, 0.0)));
+        assert!(!quad.contains_point(Vec2::new(0.0, 2.5)));
+    }
+
+    #[test]
+    fn contains_point_rotated() {
+        let quad = Quad2d::new(0.0, 0.0, 10.0, 10.0).with_rotation(FRAC_PI_4);
+
+        // Corner of the bounding box, outside of the diamond
+        assert!(!quad.contains_point(Vec2::new(4.9, 4.9)));
+        // Past the unrotated edge, inside the diamond
+        assert!(quad.contains_point(Vec2::new(0.0, 7.0)));
+        assert!(quad.contains_point(quad.top_right()));
+    }
+
+    #[test]
+    fn contains_quad_rotated() {
+        let diamond = Quad2d::new(0.0, 0.0, 10.0, 10.0).with_rotation(FRAC_PI_4);
+        assert!(diamond.contains_quad(&Quad2d::new(0.0, 0.0, 4.0, 4.0)));
+        // Inside the bounding box, but its corners stick out of the diamond
+        assert!(!diamond.contains_quad(&Quad2d::new(0.0, 0.0, 9.0, 9.0)));
+
+        let square = Quad2d::new(0.0, 0.0, 10.0, 10.0);
+        assert!(square.contains_quad(&Quad2d::new(0.0, 0.0, 6.0, 6.0).with_rotation(FRAC_PI_4)));
+        assert!(!square.contains_quad(&Quad2d::new(0.0, 0.0, 8.0, 8.0).with_rotation(FRAC_PI_4)));
+    }
+
+    #[test]
+    fn intersects_unrotated() {
+        let quad = Quad2d::new(0.0, 0.0, 10.0, 10.0);
+        assert!(quad.intersects(&Quad2d::new(8.0, 8.0, 10.0, 10.0)));
+        // Touching edges
+        assert!(quad.intersects(&Quad2d::new(10.0, 0.0, 10.0, 10.0)));
+        assert!(!quad.intersects(&Quad2d::new(11.0, 0.0, 10.0, 10.0)));
+    }
+
+    #[test]
+    fn intersects_rotated() {
+        let diamond = Quad2d::new(0.0, 0.0, 10.0, 10.0).with_rotation(FRAC_PI_4);
+
+        // Overlaps the bounding box only
+        let corner = Quad2d::new(6.0, 6.0, 2.0, 2.0);
+        assert!(!diamond.intersects(&corner));
+        assert!(!corner.intersects(&diamond));
+
+        // Reaches the tip of the diamond
+        let tip = Quad2d::new(0.0, 7.5, 2.0, 2.0);
+        assert!(diamond.intersects(&tip));
+        assert!(tip.intersects(&diamond));
+    }
+
+    #[test]
+    fn intersection_of_separated_rotated_quads() {
+        let diamond = Quad2d::new(0.0, 0.0, 10.0, 10.0).with_rotation(FRAC_PI_4);
+        assert!(diamond
+            .intersection(&Quad2d::new(6.0, 6.0, 2.0, 2.0))
+            .is_none());
+    }
+}
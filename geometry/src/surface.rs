@@ -0,0 +1,219 @@
+use glam::Vec2;
+
+/// Represents the surface of a foothold at a given x.
+/// The angle is in radians and the normal points up for footholds running left to right.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Surface {
+    pub y: f32,
+    pub angle: f32,
+    pub normal: Vec2,
+}
+
+/// Returns true if x is within the range of points of a segment running left to right.
+/// Vertical segments are walls, they have no surface and never contain x.
+pub fn segment_contains_x(p1: Vec2, p2: Vec2, x: f32) -> bool {
+    p1.x != p2.x && x >= p1.x && x <= p2.x
+}
+
+/// Gets the y coordinate if x is within the range of points of a segment.
+///
+/// # Arguments
+///
+/// * `p1`: The left point of the segment.
+/// * `p2`: The right point of the segment.
+/// * `x`: The value to evaluate for y.
+///
+/// # Examples
+/// ```
+/// use geometry::{segment_y_at_x, Vec2};
+///
+/// let y = segment_y_at_x(Vec2::new(0.0, 0.0), Vec2::new(10.0, 5.0), 4.0);
+/// assert_eq!(y, Some(2.0));
+/// ```
+pub fn segment_y_at_x(p1: Vec2, p2: Vec2, x: f32) -> Option<f32> {
+    if segment_contains_x(p1, p2, x) {
+        let slope = (p2.y - p1.y) / (p2.x - p1.x);
+        Some(p1.y + ((x - p1.x) * slope))
+    } else {
+        None
+    }
+}
+
+/// Gets the surface if x is within the range of points of a segment.
+pub fn segment_surface_at_x(p1: Vec2, p2: Vec2, x: f32) -> Option<Surface> {
+    segment_y_at_x(p1, p2, x).map(|y| {
+        let delta = p2 - p1;
+        Surface {
+            y,
+            angle: (delta.y / delta.x).atan(),
+            normal: delta.perp().normalize(),
+        }
+    })
+}
+
+/// Represents a segment running left to right, with the values used for evaluating
+/// its surface computed once, for segments that are evaluated every frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceSegment {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub slope: f32,
+    pub angle: f32,
+    pub length: f32,
+    pub normal: Vec2,
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl SurfaceSegment {
+    /// Creates a segment from its left and right points.
+    pub fn new(start: Vec2, end: Vec2) -> Self {
+        let delta = end - start;
+        let slope = delta.y / delta.x;
+        let length = delta.length();
+
+        Self {
+            start,
+            end,
+            slope,
+            angle: slope.atan(),
+            length,
+            normal: delta.perp().normalize(),
+            min: start.min(end),
+            max: start.max(end),
+        }
+    }
+
+    /// Returns true if x is within the range of points of this segment.
+    /// Vertical segments have no surface and never contain x.
+    pub fn contains_x(&self, x: f32) -> bool {
+        segment_contains_x(self.start, self.end, x)
+    }
+
+    /// Gets the y coordinate if x is within the range of points of this segment.
+    pub fn y_at(&self, x: f32) -> Option<f32> {
+        if self.contains_x(x) {
+            Some(self.start.y + ((x - self.start.x) * self.slope))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the surface if x is within the range of points of this segment.
+    pub fn surface_at(&self, x: f32) -> Option<Surface> {
+        self.y_at(x).map(|y| Surface {
+            y,
+            angle: self.angle,
+            normal: self.normal,
+        })
+    }
+}
+
+/// Gets the surface of the first segment of a chain of points containing x.
+///
+/// # Arguments
+///
+/// * `points`: The points of the chain, running left to right.
+/// * `x`: The value to evaluate for the surface.
+pub fn chain_surface_at_x(points: &[Vec2], x: f32) -> Option<Surface> {
+    points
+        .windows(2)
+        .find_map(|segment| segment_surface_at_x(segment[0], segment[1], x))
+}
+
+/// Gets the y coordinate if x is within the range of a chain of points.
+///
+/// # Examples
+/// ```
+/// use geometry::{chain_y_at_x, Vec2};
+///
+/// let points = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(20.0, 10.0)];
+/// assert_eq!(chain_y_at_x(&points, 15.0), Some(5.0));
+/// assert_eq!(chain_y_at_x(&points, 25.0), None);
+/// ```
+pub fn chain_y_at_x(points: &[Vec2], x: f32) -> Option<f32> {
+    points
+        .windows(2)
+        .find_map(|segment| segment_y_at_x(segment[0], segment[1], x))
+}
+
+/// Gets the angle in radians if x is within the range of a chain of points.
+pub fn chain_angle_at_x(points: &[Vec2], x: f32) -> Option<f32> {
+    chain_surface_at_x(points, x).map(|surface| surface.angle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    #[test]
+    fn y_at_x_within_range() {
+        let (p1, p2) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 5.0));
+        assert_eq!(segment_y_at_x(p1, p2, 0.0), Some(0.0));
+        assert_eq!(segment_y_at_x(p1, p2, 10.0), Some(5.0));
+    }
+
+    #[test]
+    fn y_at_x_outside_range() {
+        let (p1, p2) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 5.0));
+        assert_eq!(segment_y_at_x(p1, p2, -0.1), None);
+        assert_eq!(segment_y_at_x(p1, p2, 10.1), None);
+        // Segments run left to right
+        assert_eq!(segment_y_at_x(p2, p1, 5.0), None);
+    }
+
+    #[test]
+    fn y_at_x_on_vertical_segment() {
+        let (p1, p2) = (Vec2::new(5.0, 0.0), Vec2::new(5.0, 10.0));
+        assert!(!segment_contains_x(p1, p2, 5.0));
+        assert_eq!(segment_y_at_x(p1, p2, 5.0), None);
+        assert_eq!(segment_surface_at_x(p1, p2, 5.0), None);
+    }
+
+    #[test]
+    fn surface_of_slope() {
+        let surface =
+            segment_surface_at_x(Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0), 4.0).unwrap();
+        assert_eq!(surface.y, 4.0);
+        assert!((surface.angle - FRAC_PI_4).abs() < 1e-6);
+        let normal = Vec2::new(-1.0, 1.0).normalize();
+        assert!(surface.normal.distance(normal) < 1e-6);
+    }
+
+    #[test]
+    fn cached_segment_matches_free_functions() {
+        let (p1, p2) = (Vec2::new(-4.0, 2.0), Vec2::new(6.0, 7.0));
+        let segment = SurfaceSegment::new(p1, p2);
+
+        for x in [-5.0, -4.0, 0.0, 3.5, 6.0, 6.5] {
+            assert_eq!(segment.y_at(x), segment_y_at_x(p1, p2, x));
+            assert_eq!(segment.surface_at(x), segment_surface_at_x(p1, p2, x));
+        }
+        assert_eq!(segment.min, Vec2::new(-4.0, 2.0));
+        assert_eq!(segment.max, Vec2::new(6.0, 7.0));
+    }
+
+    #[test]
+    fn cached_vertical_segment_has_no_surface() {
+        let segment = SurfaceSegment::new(Vec2::new(5.0, 0.0), Vec2::new(5.0, 10.0));
+        assert!(!segment.contains_x(5.0));
+        assert_eq!(segment.surface_at(5.0), None);
+    }
+
+    #[test]
+    fn surface_of_chain() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(20.0, 10.0),
+        ];
+        assert_eq!(
+            chain_surface_at_x(&points, 5.0).map(|it| it.angle),
+            Some(0.0)
+        );
+        assert!((chain_angle_at_x(&points, 15.0).unwrap() - FRAC_PI_4).abs() < 1e-6);
+        assert_eq!(chain_surface_at_x(&points, -5.0), None);
+    }
+}
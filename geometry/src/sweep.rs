@@ -0,0 +1,671 @@
+use glam::Vec2;
+
+use crate::{capsule::*, circle::*, distance::*, line::*, polygon::*, quad::*, EPSILON};
+
+/// Represents the first contact of a shape moving along a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of the movement completed at the contact, from 0.0 to 1.0.
+    pub time: f32,
+    /// Point of contact.
+    pub point: Vec2,
+    /// Unit normal of the surface that was hit, facing against the movement.
+    pub normal: Vec2,
+}
+
+/// Calculates the first contact of a point moving by `delta` with a line
+pub fn sweep_point_line(point: Vec2, delta: Vec2, line: &Line2d) -> Option<SweepHit> {
+    let s = line.p2 - line.p1;
+    let delta_len = delta.length();
+    let s_len = s.length();
+    if delta_len < EPSILON || s_len < EPSILON {
+        return None;
+    }
+
+    // Moving parallel to the line never hits it
+    let denom = delta.perp_dot(s);
+    if denom.abs() <= EPSILON * delta_len * s_len {
+        return None;
+    }
+
+    let ap = line.p1 - point;
+    let time = ap.perp_dot(s) / denom;
+    let u = ap.perp_dot(delta) / denom;
+
+    let tolerance_time = EPSILON / delta_len;
+    let tolerance_u = EPSILON / s_len;
+    if time < -tolerance_time || time > 1.0 || u < -tolerance_u || u > 1.0 + tolerance_u {
+        return None;
+    }
+
+    let mut normal = s.perp().normalize();
+    if normal.dot(delta) > 0.0 {
+        normal = -normal;
+    }
+
+    let time = time.max(0.0);
+    Some(SweepHit {
+        time,
+        point: point + delta * time,
+        normal,
+    })
+}
+
+/// Calculates the first contact of a quad moving by `delta` with a line
+pub fn sweep_quad_line(quad: &Quad2d, delta: Vec2, line: &Line2d) -> Option<SweepHit> {
+    let corners = [
+        quad.top_left(),
+        quad.top_right(),
+        quad.bottom_right(),
+        quad.bottom_left(),
+    ];
+
+    let mut first: Option<SweepHit> = None;
+    let mut keep_first = |hit: SweepHit| {
+        if first.map_or(true, |first| hit.time < first.time) {
+            first = Some(hit);
+        }
+    };
+
+    // Corners of the quad hitting the line
+    for corner in corners {
+        if let Some(hit) = sweep_point_line(corner, delta, line) {
+            keep_first(hit);
+        }
+    }
+
+    // Ends of the line hitting the edges of the quad, moving relative to the quad
+    for it in 0..corners.len() {
+        let edge = Line2d::from_points(corners[it], corners[(it + 1) % corners.len()]);
+        for end in [line.p1, line.p2] {
+            if let Some(hit) = sweep_point_line(end, -delta, &edge) {
+                keep_first(SweepHit {
+                    time: hit.time,
+                    point: end,
+                    normal: -hit.normal,
+                });
+            }
+        }
+    }
+
+    first
+}
+
+/// Maximum number of steps taken when advancing a capsule towards a contact
+const MAX_SWEEP_STEPS: usize = 64;
+
+/// Calculates the first contact of a point moving by `delta` with a segment inflated by `radius`
+fn sweep_point_round(point: Vec2, delta: Vec2, a: Vec2, b: Vec2, radius: f32) -> Option<SweepHit> {
+    if delta.length() < EPSILON {
+        return None;
+    }
+
+    let mut first: Option<SweepHit> = None;
+    let mut keep_first = |hit: SweepHit| {
+        if first.map_or(true, |first| hit.time < first.time) {
+            first = Some(hit);
+        }
+    };
+
+    // Sides of the segment, pushed out by the radius
+    let s = b - a;
+    if s.length() >= EPSILON {
+        let offset = s.perp().normalize() * radius;
+        for side in [offset, -offset] {
+            let line = Line2d::from_points(a + side, b + side);
+            if let Some(hit) = sweep_point_line(point, delta, &line) {
+                keep_first(hit);
+            }
+        }
+    }
+
+    // Caps at both ends
+    for center in [a, b] {
+        if let Some(time) = enter_circle_time(point, delta, center, radius) {
+            keep_first(SweepHit {
+                time,
+                point: point + delta * time,
+                normal: Vec2::ZERO,
+            });
+        }
+    }
+
+    // Normals point from the core to the contact, so they face against the movement
+    first.map(|hit| {
+        let closest = closest_point_on_segment(a, b, hit.point).point;
+        let normal = (hit.point - closest).normalize_or_zero();
+        SweepHit {
+            normal: if normal == Vec2::ZERO {
+                -delta.normalize()
+            } else {
+                normal
+            },
+            ..hit
+        }
+    })
+}
+
+/// Calculates the time a point moving by `delta` enters a circle
+fn enter_circle_time(point: Vec2, delta: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let m = point - center;
+    let a = delta.dot(delta);
+    let b = m.dot(delta);
+    let c = m.dot(m) - radius * radius;
+
+    // Starting outside and moving away, or never reaching the circle
+    if c > 0.0 && b > 0.0 {
+        return None;
+    }
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let time = ((-b - discriminant.sqrt()) / a).max(0.0);
+    if time > 1.0 {
+        None
+    } else {
+        Some(time)
+    }
+}
+
+/// Calculates the first contact of a point moving by `delta` with a quad.
+/// A point starting inside the quad hits it right away.
+pub fn sweep_point_quad(point: Vec2, delta: Vec2, quad: &Quad2d) -> Option<SweepHit> {
+    // Work relative to the unrotated quad
+    let (right, up) = quad.axes();
+    let local_point = quad.to_local(point);
+    let local_delta = Vec2::new(delta.dot(right), delta.dot(up));
+    let max = Vec2::new(quad.width / 2.0, quad.height / 2.0);
+    let min = -max;
+
+    // Clip the movement against the x and y slabs of the quad
+    let mut time_in = 0.0_f32;
+    let mut time_out = 1.0_f32;
+    let mut normal = Vec2::ZERO;
+    for (axis, unit) in [(0, right), (1, up)] {
+        let (p, d, lo, hi) = (local_point[axis], local_delta[axis], min[axis], max[axis]);
+        if d.abs() < EPSILON {
+            if p < lo || p > hi {
+                return None;
+            }
+            continue;
+        }
+
+        let (t1, t2) = ((lo - p) / d, (hi - p) / d);
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > time_in {
+            time_in = near;
+            normal = if d > 0.0 { -unit } else { unit };
+        }
+        time_out = time_out.min(far);
+        if time_in > time_out {
+            return None;
+        }
+    }
+
+    if normal == Vec2::ZERO {
+        normal = -delta.normalize_or_zero();
+    }
+    Some(SweepHit {
+        time: time_in,
+        point: point + delta * time_in,
+        normal,
+    })
+}
+
+/// Calculates the first contact of a point moving by `delta` with a polygon.
+/// A point starting inside the polygon hits it right away.
+pub fn sweep_point_polygon(
+    point: Vec2,
+    delta: Vec2,
+    polygon: &ConvexPolygon2d,
+) -> Option<SweepHit> {
+    if polygon.contains_point(point) {
+        return Some(SweepHit {
+            time: 0.0,
+            point,
+            normal: -delta.normalize_or_zero(),
+        });
+    }
+
+    polygon
+        .edges()
+        .iter()
+        .filter_map(|(p1, p2)| sweep_point_line(point, delta, &Line2d::from_points(*p1, *p2)))
+        .min_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// Calculates the first contact of a point moving by `delta` with a circle.
+/// A point starting inside the circle hits it right away.
+pub fn sweep_point_circle(point: Vec2, delta: Vec2, circle: &Circle2d) -> Option<SweepHit> {
+    sweep_point_round_solid(
+        point,
+        delta,
+        circle.position,
+        circle.position,
+        circle.radius,
+    )
+}
+
+/// Calculates the first contact of a point moving by `delta` with a capsule.
+/// A point starting inside the capsule hits it right away.
+pub fn sweep_point_capsule(point: Vec2, delta: Vec2, capsule: &Capsule2d) -> Option<SweepHit> {
+    sweep_point_round_solid(
+        point,
+        delta,
+        capsule.bottom(),
+        capsule.top(),
+        capsule.radius,
+    )
+}
+
+/// Like `sweep_point_round`, but a point starting inside the shape hits it right away
+fn sweep_point_round_solid(
+    point: Vec2,
+    delta: Vec2,
+    a: Vec2,
+    b: Vec2,
+    radius: f32,
+) -> Option<SweepHit> {
+    if point.distance(closest_point_on_segment(a, b, point).point) < radius {
+        return Some(SweepHit {
+            time: 0.0,
+            point,
+            normal: -delta.normalize_or_zero(),
+        });
+    }
+    sweep_point_round(point, delta, a, b, radius)
+}
+
+/// Moves the contact of a sweep from the core of a round shape to its surface
+fn to_surface(hit: SweepHit, radius: f32) -> SweepHit {
+    SweepHit {
+        point: hit.point - hit.normal * radius,
+        ..hit
+    }
+}
+
+/// Calculates the first contact of a circle moving by `delta` with a line
+pub fn sweep_circle_line(circle: &Circle2d, delta: Vec2, line: &Line2d) -> Option<SweepHit> {
+    sweep_point_round(circle.position, delta, line.p1, line.p2, circle.radius)
+        .map(|hit| to_surface(hit, circle.radius))
+}
+
+/// Calculates the first contact of circle A moving by `delta` with circle B
+pub fn sweep_circle_circle(a: &Circle2d, delta: Vec2, b: &Circle2d) -> Option<SweepHit> {
+    sweep_point_round(
+        a.position,
+        delta,
+        b.position,
+        b.position,
+        a.radius + b.radius,
+    )
+    .map(|hit| to_surface(hit, a.radius))
+}
+
+/// Calculates the first contact of a circle moving by `delta` with a capsule
+pub fn sweep_circle_capsule(
+    circle: &Circle2d,
+    delta: Vec2,
+    capsule: &Capsule2d,
+) -> Option<SweepHit> {
+    sweep_point_round(
+        circle.position,
+        delta,
+        capsule.bottom(),
+        capsule.top(),
+        circle.radius + capsule.radius,
+    )
+    .map(|hit| to_surface(hit, circle.radius))
+}
+
+/// Calculates the first contact of a capsule moving by `delta` with a line
+pub fn sweep_capsule_line(capsule: &Capsule2d, delta: Vec2, line: &Line2d) -> Option<SweepHit> {
+    sweep_round_round(
+        (capsule.bottom(), capsule.top()),
+        capsule.radius,
+        delta,
+        (line.p1, line.p2),
+        0.0,
+    )
+}
+
+/// Calculates the first contact of capsule A moving by `delta` with capsule B
+pub fn sweep_capsule_capsule(a: &Capsule2d, delta: Vec2, b: &Capsule2d) -> Option<SweepHit> {
+    sweep_round_round(
+        (a.bottom(), a.top()),
+        a.radius,
+        delta,
+        (b.bottom(), b.top()),
+        b.radius,
+    )
+}
+
+/// Calculates the first contact of round shape A moving by `delta` with round shape B,
+/// by conservative advancement: A moves by the gap between the shapes until they touch
+fn sweep_round_round(
+    a: (Vec2, Vec2),
+    radius_a: f32,
+    delta: Vec2,
+    b: (Vec2, Vec2),
+    radius_b: f32,
+) -> Option<SweepHit> {
+    let delta_len = delta.length();
+    if delta_len < EPSILON {
+        return None;
+    }
+
+    let mut time = 0.0;
+    for _ in 0..MAX_SWEEP_STEPS {
+        let offset = delta * time;
+        let (closest_a, closest_b) =
+            closest_points_segment_segment(a.0 + offset, a.1 + offset, b.0, b.1);
+        let core_distance = closest_a.distance(closest_b);
+        let gap = core_distance - radius_a - radius_b;
+
+        if gap <= EPSILON {
+            let normal = if core_distance < EPSILON {
+                -delta / delta_len
+            } else {
+                (closest_a - closest_b) / core_distance
+            };
+
+            // Already touching and moving away
+            if normal.dot(delta) > 0.0 {
+                return None;
+            }
+            return Some(SweepHit {
+                time,
+                point: closest_b + normal * radius_b,
+                normal,
+            });
+        }
+
+        // The gap can shrink by at most the length of the movement
+        time += gap / delta_len;
+        if time > 1.0 {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// Keeps the earliest of 2 sweep hits
+fn first_hit(a: Option<SweepHit>, b: Option<SweepHit>) -> Option<SweepHit> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.time < a.time { b } else { a }),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// Calculates the first contact of a polygon moving by `delta` with a line
+pub fn sweep_polygon_line(
+    polygon: &ConvexPolygon2d,
+    delta: Vec2,
+    line: &Line2d,
+) -> Option<SweepHit> {
+    // Points of the polygon hitting the line
+    let points = polygon
+        .world_points()
+        .iter()
+        .map(|point| sweep_point_line(*point, delta, line))
+        .fold(None, first_hit);
+
+    // Ends of the line hitting the edges of the polygon, moving relative to the polygon
+    let ends = polygon
+        .edges()
+        .iter()
+        .flat_map(|(p1, p2)| {
+            let edge = Line2d::from_points(*p1, *p2);
+            [line.p1, line.p2].map(|end| {
+                sweep_point_line(end, -delta, &edge).map(|hit| SweepHit {
+                    time: hit.time,
+                    point: end,
+                    normal: -hit.normal,
+                })
+            })
+        })
+        .fold(None, first_hit);
+
+    first_hit(points, ends)
+}
+
+/// Calculates the first contact of polygon A moving by `delta` with polygon B
+pub fn sweep_polygon_polygon(
+    a: &ConvexPolygon2d,
+    delta: Vec2,
+    b: &ConvexPolygon2d,
+) -> Option<SweepHit> {
+    b.edges()
+        .iter()
+        .map(|(p1, p2)| sweep_polygon_line(a, delta, &Line2d::from_points(*p1, *p2)))
+        .fold(None, first_hit)
+}
+
+/// Calculates the first contact of a circle moving by `delta` with a polygon
+pub fn sweep_circle_polygon(
+    circle: &Circle2d,
+    delta: Vec2,
+    polygon: &ConvexPolygon2d,
+) -> Option<SweepHit> {
+    polygon
+        .edges()
+        .iter()
+        .map(|(p1, p2)| sweep_circle_line(circle, delta, &Line2d::from_points(*p1, *p2)))
+        .fold(None, first_hit)
+}
+
+/// Calculates the first contact of a capsule moving by `delta` with a polygon
+pub fn sweep_capsule_polygon(
+    capsule: &Capsule2d,
+    delta: Vec2,
+    polygon: &ConvexPolygon2d,
+) -> Option<SweepHit> {
+    polygon
+        .edges()
+        .iter()
+        .map(|(p1, p2)| sweep_capsule_line(capsule, delta, &Line2d::from_points(*p1, *p2)))
+        .fold(None, first_hit)
+}
+
+/// Turns the hit of a static shape swept by `-delta` into the hit of the moving shape
+pub fn reverse_sweep_hit(hit: SweepHit, delta: Vec2) -> SweepHit {
+    SweepHit {
+        time: hit.time,
+        point: hit.point + delta * hit.time,
+        normal: -hit.normal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floor() -> Line2d {
+        Line2d::new(-10.0, 0.0, 10.0, 0.0)
+    }
+
+    fn assert_hit(hit: Option<SweepHit>, time: f32, point: Vec2, normal: Vec2) {
+        let hit = hit.expect("expected a hit");
+        assert!(
+            (hit.time - time).abs() < 0.01,
+            "time {} != {}",
+            hit.time,
+            time
+        );
+        assert!(
+            hit.point.distance(point) < 0.01,
+            "point {:?} != {:?}",
+            hit.point,
+            point
+        );
+        assert!(
+            hit.normal.distance(normal) < EPSILON,
+            "normal {:?} != {:?}",
+            hit.normal,
+            normal
+        );
+    }
+
+    #[test]
+    fn point_line_hit() {
+        let hit = sweep_point_line(Vec2::new(0.0, 10.0), Vec2::new(0.0, -20.0), &floor());
+        assert_hit(hit, 0.5, Vec2::new(0.0, 0.0), Vec2::Y);
+
+        // Coming from below faces the other way
+        let hit = sweep_point_line(Vec2::new(0.0, -10.0), Vec2::new(0.0, 20.0), &floor());
+        assert_hit(hit, 0.5, Vec2::new(0.0, 0.0), -Vec2::Y);
+    }
+
+    #[test]
+    fn point_line_miss() {
+        let line = floor();
+        // Stops short
+        assert_eq!(
+            sweep_point_line(Vec2::new(0.0, 10.0), Vec2::new(0.0, -5.0), &line),
+            None
+        );
+        // Passes beside the line
+        assert_eq!(
+            sweep_point_line(Vec2::new(15.0, 10.0), Vec2::new(0.0, -20.0), &line),
+            None
+        );
+        // Moves parallel to the line
+        assert_eq!(
+            sweep_point_line(Vec2::new(-20.0, 0.0), Vec2::new(40.0, 0.0), &line),
+            None
+        );
+        // Does not move
+        assert_eq!(
+            sweep_point_line(Vec2::new(0.0, 0.0), Vec2::ZERO, &line),
+            None
+        );
+    }
+
+    #[test]
+    fn quad_line_hit() {
+        // Only the bottom right corner is above the line
+        let quad = Quad2d::new(-11.0, 10.0, 4.0, 4.0);
+        let hit = sweep_quad_line(&quad, Vec2::new(0.0, -20.0), &floor());
+        assert_hit(hit, 0.4, Vec2::new(-9.0, 0.0), Vec2::Y);
+    }
+
+    #[test]
+    fn quad_line_hit_by_line_end() {
+        // Only the end of the line reaches the side of the quad
+        let quad = Quad2d::new(20.0, 0.0, 4.0, 4.0);
+        let hit = sweep_quad_line(&quad, Vec2::new(-20.0, 0.0), &floor());
+        assert_hit(hit, 0.4, Vec2::new(10.0, 0.0), Vec2::X);
+    }
+
+    #[test]
+    fn point_quad_hit() {
+        let quad = Quad2d::new(0.0, 0.0, 10.0, 10.0);
+
+        let hit = sweep_point_quad(Vec2::new(-10.0, 0.0), Vec2::new(20.0, 0.0), &quad);
+        assert_hit(hit, 0.25, Vec2::new(-5.0, 0.0), -Vec2::X);
+
+        let inside = sweep_point_quad(Vec2::new(1.0, 1.0), Vec2::new(20.0, 0.0), &quad);
+        assert_eq!(inside.map(|hit| hit.time), Some(0.0));
+
+        let miss = sweep_point_quad(Vec2::new(-10.0, 6.0), Vec2::new(20.0, 0.0), &quad);
+        assert_eq!(miss, None);
+    }
+
+    #[test]
+    fn point_polygon_hit() {
+        let polygon = ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 10.0, 10.0));
+
+        let hit = sweep_point_polygon(Vec2::new(0.0, 10.0), Vec2::new(0.0, -10.0), &polygon);
+        assert_hit(hit, 0.5, Vec2::new(0.0, 5.0), Vec2::Y);
+    }
+
+    #[test]
+    fn point_circle_hit() {
+        let circle = Circle2d::new(0.0, 0.0, 5.0);
+
+        let hit = sweep_point_circle(Vec2::new(-10.0, 0.0), Vec2::new(20.0, 0.0), &circle);
+        assert_hit(hit, 0.25, Vec2::new(-5.0, 0.0), -Vec2::X);
+
+        let inside = sweep_point_circle(Vec2::new(1.0, 0.0), Vec2::new(20.0, 0.0), &circle);
+        assert_hit(inside, 0.0, Vec2::new(1.0, 0.0), -Vec2::X);
+
+        let miss = sweep_point_circle(Vec2::new(-10.0, 6.0), Vec2::new(20.0, 0.0), &circle);
+        assert_eq!(miss, None);
+    }
+
+    #[test]
+    fn point_capsule_hit() {
+        let capsule = Capsule2d::new(0.0, 0.0, 10.0, 2.0);
+
+        let hit = sweep_point_capsule(Vec2::new(-10.0, 2.0), Vec2::new(20.0, 0.0), &capsule);
+        assert_hit(hit, 0.4, Vec2::new(-2.0, 2.0), -Vec2::X);
+    }
+
+    #[test]
+    fn circle_line_hit() {
+        let circle = Circle2d::new(0.0, 10.0, 2.0);
+        let hit = sweep_circle_line(&circle, Vec2::new(0.0, -20.0), &floor());
+        assert_hit(hit, 0.4, Vec2::new(0.0, 0.0), Vec2::Y);
+    }
+
+    #[test]
+    fn circle_circle_hit() {
+        let a = Circle2d::new(-10.0, 0.0, 1.0);
+        let b = Circle2d::new(0.0, 0.0, 1.0);
+
+        let hit = sweep_circle_circle(&a, Vec2::new(20.0, 0.0), &b);
+        assert_hit(hit, 0.4, Vec2::new(-1.0, 0.0), -Vec2::X);
+
+        assert_eq!(sweep_circle_circle(&a, Vec2::new(-20.0, 0.0), &b), None);
+    }
+
+    #[test]
+    fn capsule_line_hit() {
+        let capsule = Capsule2d::new(0.0, 20.0, 10.0, 2.0);
+        let hit = sweep_capsule_line(&capsule, Vec2::new(0.0, -20.0), &floor());
+        assert_hit(hit, 0.75, Vec2::new(0.0, 0.0), Vec2::Y);
+    }
+
+    #[test]
+    fn capsule_capsule_hit() {
+        let a = Capsule2d::new(-10.0, 0.0, 10.0, 1.0);
+        let b = Capsule2d::new(0.0, 0.0, 10.0, 1.0);
+
+        let hit = sweep_capsule_capsule(&a, Vec2::new(20.0, 0.0), &b);
+        let hit = hit.expect("expected a hit");
+        assert!((hit.time - 0.4).abs() < 0.01);
+        assert!(hit.normal.distance(-Vec2::X) < EPSILON);
+        assert!((hit.point.x + 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn polygon_polygon_hit() {
+        let a = ConvexPolygon2d::from(&Quad2d::new(-10.0, 0.0, 4.0, 4.0));
+        let b = ConvexPolygon2d::from(&Quad2d::new(0.0, 0.0, 4.0, 4.0));
+
+        let hit = sweep_polygon_polygon(&a, Vec2::new(20.0, 0.0), &b).expect("expected a hit");
+        assert!((hit.time - 0.3).abs() < 0.01);
+        assert!(hit.normal.distance(-Vec2::X) < EPSILON);
+    }
+
+    #[test]
+    fn reversed_hit() {
+        let hit = SweepHit {
+            time: 0.5,
+            point: Vec2::new(0.0, 0.0),
+            normal: Vec2::Y,
+        };
+        let reversed = reverse_sweep_hit(hit, Vec2::new(0.0, -10.0));
+        assert_eq!(reversed.time, 0.5);
+        assert_eq!(reversed.point, Vec2::new(0.0, -5.0));
+        assert_eq!(reversed.normal, -Vec2::Y);
+    }
+}
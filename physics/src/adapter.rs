@@ -0,0 +1,35 @@
+/// Implements the traits that let a component wrapping a geometry shape be used as the shape.
+///
+/// The shapes live in the geometry crate, which has no Bevy dependency,
+/// so the components only add `Component` and forward everything else.
+macro_rules! shape_component {
+    ($component:ident, $shape:ty) => {
+        impl std::ops::Deref for $component {
+            type Target = $shape;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl std::ops::DerefMut for $component {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl From<$shape> for $component {
+            fn from(shape: $shape) -> Self {
+                Self(shape)
+            }
+        }
+
+        impl From<$component> for $shape {
+            fn from(component: $component) -> Self {
+                component.0
+            }
+        }
+    };
+}
+
+pub(crate) use shape_component;
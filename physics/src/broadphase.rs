@@ -0,0 +1,322 @@
+use bevy::prelude::*;
+
+use super::{capsule::*, circle::*, groups::*, polygon::*, quad::*, query::*};
+
+/// Represents the bounding box of a collider shape.
+#[derive(Clone, Copy, Debug)]
+struct Proxy {
+    entity: Entity,
+    groups: CollisionGroups,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl Proxy {
+    fn overlaps(&self, min: Vec2, max: Vec2) -> bool {
+        self.min.x <= max.x && self.max.x >= min.x && self.min.y <= max.y && self.max.y >= min.y
+    }
+}
+
+/// Holds the bounding boxes of every collider entity, sorted along x (sweep and prune).
+///
+/// The boxes are rebuilt at the start of every frame, so the candidate pairs and overlap
+/// queries reflect where colliders were at the end of the previous frame.
+/// Candidates only share bounding boxes, the exact shapes still need to be tested.
+#[derive(Default)]
+pub struct Broadphase {
+    proxies: Vec<Proxy>,
+    pairs: Vec<(Entity, Entity)>,
+}
+
+impl Broadphase {
+    /// Gets the pairs of entities whose bounding boxes overlap and whose groups interact.
+    pub fn pairs(&self) -> &[(Entity, Entity)] {
+        &self.pairs
+    }
+
+    /// Gets the entities whose bounding boxes overlap a rectangle.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect`: The rectangle to test.
+    /// * `filter`: The entities that can be returned.
+    ///
+    /// # Examples
+    /// ```
+    /// let area = Quad2d::new(0.0, 0.0, 100.0, 100.0);
+    /// for entity in broadphase.overlap_aabb(&area, &QueryFilter::default()) {
+    ///     println!("{:?}", entity);
+    /// }
+    /// ```
+    pub fn overlap_aabb(&self, rect: &Quad2d, filter: &QueryFilter) -> Vec<Entity> {
+        let (min, max) = rect.bounds();
+        self.overlapping(min, max, filter)
+    }
+
+    /// Gets the entities whose bounding boxes contain a point.
+    ///
+    /// # Arguments
+    ///
+    /// * `point`: The point to test.
+    /// * `filter`: The entities that can be returned.
+    pub fn overlap_point(&self, point: Vec2, filter: &QueryFilter) -> Vec<Entity> {
+        self.overlapping(point, point, filter)
+    }
+
+    fn overlapping(&self, min: Vec2, max: Vec2, filter: &QueryFilter) -> Vec<Entity> {
+        // Proxies past the right edge can never overlap
+        let end = self.proxies.partition_point(|proxy| proxy.min.x <= max.x);
+
+        let mut entities: Vec<Entity> = self.proxies[..end]
+            .iter()
+            .filter(|proxy| proxy.overlaps(min, max))
+            .filter(|proxy| filter.allows(proxy.entity, Some(&proxy.groups)))
+            .map(|proxy| proxy.entity)
+            .collect();
+        // Entities with several shapes are only listed once
+        entities.sort();
+        entities.dedup();
+        entities
+    }
+
+    /// Replaces the bounding boxes and recalculates the candidate pairs.
+    fn rebuild(&mut self, proxies: Vec<Proxy>) {
+        self.proxies = proxies;
+        self.proxies.sort_by(|a, b| {
+            a.min
+                .x
+                .partial_cmp(&b.min.x)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        self.pairs.clear();
+        for (it, a) in self.proxies.iter().enumerate() {
+            // Stop once the boxes start past the right edge of this one
+            for b in self.proxies[it + 1..]
+                .iter()
+                .take_while(|b| b.min.x <= a.max.x)
+            {
+                if a.entity != b.entity
+                    && a.groups.interacts_with(&b.groups)
+                    && b.overlaps(a.min, a.max)
+                {
+                    self.pairs
+                        .push((a.entity.min(b.entity), a.entity.max(b.entity)));
+                }
+            }
+        }
+
+        // Entities with several shapes are only paired once
+        self.pairs.sort();
+        self.pairs.dedup();
+    }
+}
+
+/// Plugin for the entity broadphase.
+pub struct BroadphasePlugin;
+
+impl Plugin for BroadphasePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Broadphase>()
+            .add_system_to_stage(CoreStage::PreUpdate, broadphase_system);
+    }
+}
+
+/// System used for rebuilding the broadphase from every collider shape.
+fn broadphase_system(
+    mut broadphase: ResMut<Broadphase>,
+    quads: Query<(Entity, &Quad2d, Option<&CollisionGroups>)>,
+    polygons: Query<(Entity, &ConvexPolygon2d, Option<&CollisionGroups>)>,
+    circles: Query<(Entity, &Circle2d, Option<&CollisionGroups>)>,
+    capsules: Query<(Entity, &Capsule2d, Option<&CollisionGroups>)>,
+) {
+    let proxy =
+        |entity: Entity, groups: Option<&CollisionGroups>, (min, max): (Vec2, Vec2)| Proxy {
+            entity,
+            groups: groups.copied().unwrap_or_default(),
+            min,
+            max,
+        };
+
+    let mut proxies = Vec::new();
+    proxies.extend(
+        quads
+            .iter()
+            .map(|(it, quad, groups)| proxy(it, groups, quad.bounds())),
+    );
+    proxies.extend(
+        polygons
+            .iter()
+            .map(|(it, polygon, groups)| proxy(it, groups, polygon.bounds())),
+    );
+    proxies.extend(
+        circles
+            .iter()
+            .map(|(it, circle, groups)| proxy(it, groups, circle.bounds())),
+    );
+    proxies.extend(
+        capsules
+            .iter()
+            .map(|(it, capsule, groups)| proxy(it, groups, capsule.bounds())),
+    );
+
+    broadphase.rebuild(proxies);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(entity: u32, groups: CollisionGroups, min: Vec2, max: Vec2) -> Proxy {
+        Proxy {
+            entity: Entity::from_raw(entity),
+            groups,
+            min,
+            max,
+        }
+    }
+
+    /// Creates boxes scattered with a fixed seed, some belonging to the same entity.
+    fn scattered(count: u32) -> Vec<Proxy> {
+        let mut seed: u32 = 12345;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as f32 / 65536.0
+        };
+        let groups = [
+            CollisionGroups::default(),
+            CollisionGroups::new(GROUP_PLAYER, GROUP_ALL ^ GROUP_PLAYER),
+            CollisionGroups::new(GROUP_MOB, GROUP_PLAYER),
+        ];
+
+        (0..count)
+            .map(|it| {
+                let min = Vec2::new(next() * 500.0, next() * 500.0);
+                let size = Vec2::new(next() * 60.0, next() * 60.0);
+                let groups = groups[(next() * groups.len() as f32) as usize % groups.len()];
+                proxy(it / 2, groups, min, min + size)
+            })
+            .collect()
+    }
+
+    /// Gets the candidate pairs by testing every pair of boxes.
+    fn brute_force_pairs(proxies: &[Proxy]) -> Vec<(Entity, Entity)> {
+        let mut pairs = Vec::new();
+        for (it, a) in proxies.iter().enumerate() {
+            for b in proxies[it + 1..].iter() {
+                if a.entity != b.entity
+                    && a.groups.interacts_with(&b.groups)
+                    && b.overlaps(a.min, a.max)
+                {
+                    pairs.push((a.entity.min(b.entity), a.entity.max(b.entity)));
+                }
+            }
+        }
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    #[test]
+    fn pairs_match_brute_force() {
+        let proxies = scattered(200);
+        let expected = brute_force_pairs(&proxies);
+        assert!(!expected.is_empty());
+
+        let mut broadphase = Broadphase::default();
+        broadphase.rebuild(proxies);
+        assert_eq!(broadphase.pairs(), expected.as_slice());
+    }
+
+    #[test]
+    fn pairs_touching_and_contained() {
+        let groups = CollisionGroups::default();
+        let mut broadphase = Broadphase::default();
+        broadphase.rebuild(vec![
+            proxy(0, groups, Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0)),
+            // Touches the right edge of 0
+            proxy(1, groups, Vec2::new(10.0, 0.0), Vec2::new(20.0, 10.0)),
+            // Inside 0
+            proxy(2, groups, Vec2::new(2.0, 2.0), Vec2::new(4.0, 4.0)),
+            // Overlaps 0 along x only
+            proxy(3, groups, Vec2::new(5.0, 20.0), Vec2::new(15.0, 30.0)),
+        ]);
+
+        let (e0, e1, e2) = (
+            Entity::from_raw(0),
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+        );
+        assert_eq!(broadphase.pairs(), &[(e0, e1), (e0, e2)]);
+    }
+
+    #[test]
+    fn overlap_queries() {
+        let mut broadphase = Broadphase::default();
+        broadphase.rebuild(vec![
+            proxy(
+                0,
+                CollisionGroups::default(),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 10.0),
+            ),
+            proxy(
+                1,
+                CollisionGroups::default(),
+                Vec2::new(20.0, 0.0),
+                Vec2::new(30.0, 10.0),
+            ),
+            // Second shape of 1
+            proxy(
+                1,
+                CollisionGroups::default(),
+                Vec2::new(25.0, 5.0),
+                Vec2::new(35.0, 15.0),
+            ),
+            proxy(
+                2,
+                CollisionGroups::new(GROUP_MOB, GROUP_MOB),
+                Vec2::new(5.0, 5.0),
+                Vec2::new(25.0, 25.0),
+            ),
+        ]);
+        let (e0, e1, e2) = (
+            Entity::from_raw(0),
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+        );
+        let filter = QueryFilter::default();
+
+        // Centered at (15, 5), covering x from 5 to 25
+        let area = Quad2d::new(15.0, 5.0, 20.0, 2.0);
+        assert_eq!(broadphase.overlap_aabb(&area, &filter), vec![e0, e1, e2]);
+        let far = Quad2d::new(100.0, 100.0, 10.0, 10.0);
+        assert!(broadphase.overlap_aabb(&far, &filter).is_empty());
+
+        // Entities with several shapes are listed once
+        assert_eq!(
+            broadphase.overlap_point(Vec2::new(27.0, 7.0), &filter),
+            vec![e1]
+        );
+        assert_eq!(
+            broadphase.overlap_point(Vec2::new(8.0, 8.0), &filter),
+            vec![e0, e2]
+        );
+        assert!(broadphase
+            .overlap_point(Vec2::new(15.0, 2.0), &filter)
+            .is_empty());
+
+        // Filtered by entity and groups
+        let excluding = QueryFilter::default().exclude(e0);
+        assert_eq!(
+            broadphase.overlap_point(Vec2::new(8.0, 8.0), &excluding),
+            vec![e2]
+        );
+        let players =
+            QueryFilter::default().with_groups(CollisionGroups::new(GROUP_PLAYER, GROUP_ALL));
+        assert_eq!(
+            broadphase.overlap_point(Vec2::new(8.0, 8.0), &players),
+            vec![e0]
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+use super::adapter::shape_component;
+
+/// Represents an upright capsule: a vertical segment inflated by a radius
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct Capsule2d(pub geometry::Capsule2d);
+
+shape_component!(Capsule2d, geometry::Capsule2d);
+
+impl Capsule2d {
+    /// Creates a new capsule centered at (x, y), with a total height including both caps
+    pub fn new(x: f32, y: f32, height: f32, radius: f32) -> Self {
+        Self(geometry::Capsule2d::new(x, y, height, radius))
+    }
+}
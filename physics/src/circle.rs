@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+use super::adapter::shape_component;
+
+/// Represents a circle with a center and radius
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct Circle2d(pub geometry::Circle2d);
+
+shape_component!(Circle2d, geometry::Circle2d);
+
+impl Circle2d {
+    pub fn new(x: f32, y: f32, radius: f32) -> Self {
+        Self(geometry::Circle2d::new(x, y, radius))
+    }
+}
@@ -1,9 +1,12 @@
 use bevy::prelude::*;
+use geometry::{Surface, SurfaceSegment};
 
 /// Represents a foothold as a set of points.
+/// The geometry of every segment is computed once, when the foothold is created.
 #[derive(Clone, Component, Debug)]
 pub struct Foothold {
-    pub points: Vec<Vec2>,
+    points: Vec<Vec2>,
+    segments: Vec<SurfaceSegment>,
 }
 
 impl Foothold {
@@ -24,9 +27,23 @@ impl Foothold {
     pub fn from_points(points: &[Vec2]) -> Self {
         Foothold {
             points: points.to_vec(),
+            segments: points
+                .windows(2)
+                .map(|segment| SurfaceSegment::new(segment[0], segment[1]))
+                .collect(),
         }
     }
 
+    /// Gets the points of this foothold.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Gets the segments of this foothold, between every pair of points.
+    pub fn segments(&self) -> &[SurfaceSegment] {
+        &self.segments
+    }
+
     /// Gets the y coordinate if x is within the range of points of this foothold.
     ///
     /// # Arguments
@@ -41,21 +58,18 @@ impl Foothold {
     /// };
     /// ```
     pub fn get_y_at_x(&self, x: f32) -> Option<f32> {
-        let points = &self.points;
-
-        // Loop through each pair of points
-        for it in 1..points.len() {
-            let p1 = points[it - 1];
-            let p2 = points[it];
+        self.segments.iter().find_map(|segment| segment.y_at(x))
+    }
 
-            // Check if 2 points contain x
-            if x >= p1.x && x <= p2.x {
-                let slope = (p2.y - p1.y) / (p2.x - p1.x);
-                let y = p2.y + ((x - p2.x) * slope);
-                return Some(y);
-            }
-        }
-        None
+    /// Gets the surface if x is within the range of points of this foothold.
+    ///
+    /// # Arguments
+    ///
+    /// * `x`: The value to evaluate for the surface.
+    pub fn get_surface_at_x(&self, x: f32) -> Option<Surface> {
+        self.segments
+            .iter()
+            .find_map(|segment| segment.surface_at(x))
     }
 
     /// Gets the angle if x is within the range of points of this foothold.
@@ -73,21 +87,7 @@ impl Foothold {
     /// };
     /// ```
     pub fn get_angle_at_x(&self, x: f32) -> Option<f32> {
-        let points = &self.points;
-
-        // Loop through each pair of points
-        for it in 1..points.len() {
-            let p1 = points[it - 1];
-            let p2 = points[it];
-
-            // Check if 2 points contain x
-            if x >= p1.x && x <= p2.x {
-                let slope = (p2.y - p1.y) / (p2.x - p1.x);
-                let angle = slope.atan();
-                return Some(angle);
-            }
-        }
-        None
+        self.get_surface_at_x(x).map(|surface| surface.angle)
     }
 }
 
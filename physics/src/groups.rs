@@ -0,0 +1,2 @@
+// Collision groups are shared with the other crates through the shared crate
+pub use shared::groups::*;
@@ -1,23 +1,21 @@
 use bevy::prelude::*;
 
+use super::adapter::shape_component;
+
 /// Represents a line with 2 points
-#[derive(Debug, Clone, Component)]
-pub struct Line2d {
-    pub p1: Vec2,
-    pub p2: Vec2,
-}
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct Line2d(pub geometry::Line2d);
+
+shape_component!(Line2d, geometry::Line2d);
 
 impl Line2d {
     /// Creates a new line from values (x1, y1) to (x2, y2)
     pub fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
-        Self {
-            p1: Vec2::new(x1, y1),
-            p2: Vec2::new(x2, y2),
-        }
+        Self(geometry::Line2d::new(x1, y1, x2, y2))
     }
 
     /// Creates a new line from points p1 to p2
     pub fn from_points(p1: Vec2, p2: Vec2) -> Self {
-        Self { p1, p2 }
+        Self(geometry::Line2d::from_points(p1, p2))
     }
 }
@@ -1,29 +1,46 @@
 use bevy::prelude::*;
 
+mod adapter;
+mod broadphase;
 mod camera;
+mod capsule;
+mod circle;
 mod collider;
 mod foothold;
+mod groups;
 mod line;
 mod player;
+mod polygon;
 mod quad;
+mod query;
 mod render;
 mod rigid_body;
+mod trigger;
 
+use broadphase::*;
 use camera::*;
+use capsule::*;
+use circle::*;
 use collider::*;
 use foothold::*;
+use groups::*;
 use line::*;
 use player::*;
+use polygon::*;
 use quad::*;
+use query::*;
 use render::*;
 use rigid_body::*;
+use trigger::*;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugin(CameraPlugin)
         .add_plugin(RenderPlugin)
-        .add_plugin(RigidBodyPlugin)
+        .add_plugin(RigidBodyPlugin::<Quad2d>::default())
+        .add_plugin(BroadphasePlugin)
+        .add_plugin(TriggerPlugin)
         .add_plugin(PlayerPlugin)
         .add_startup_system(setup)
         .add_startup_system(new_player_follow_camera)
@@ -1,17 +1,19 @@
-use bevy::{core::FixedTimestep, prelude::*};
+use std::cmp::Ordering;
 
-use crate::{foothold::*, quad::*, render::*, rigid_body::*};
+use bevy::prelude::*;
 
-const TIMESTEP_60_FRAMES_PER_SECOND: f64 = 1.0 / 60.0;
-const TIMESTEP_LABEL: &str = "player_timestep";
+use crate::{collider::*, foothold::*, groups::*, line::*, quad::*, render::*, rigid_body::*};
 
 pub fn spawn_player(mut commands: Commands) {
+    let quad = Quad2d::new(0.0, 100.0, 20.0, 40.0);
     commands
         .spawn()
         .insert(PlayerState::default())
-        .insert(Quad2d::new(0.0, 100.0, 20.0, 40.0))
+        .insert(PreviousStep::from(&quad))
+        .insert(quad)
         .insert(GlobalTransform::default())
         .insert(RigidBody::default())
+        .insert(CollisionGroups::new(GROUP_PLAYER, GROUP_ALL))
         .insert(RenderColor::default())
         .insert(Player);
 }
@@ -19,6 +21,10 @@ pub fn spawn_player(mut commands: Commands) {
 #[derive(Clone, Component, Debug)]
 pub struct Player;
 
+/// Marks a player whose quad tilts to the angle of the foothold it stands on.
+#[derive(Clone, Component, Debug, Default)]
+pub struct TiltToSlope;
+
 #[derive(Clone, Component, Debug, Default)]
 pub struct PlayerState {
     ground: bool,
@@ -29,52 +35,30 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_system(player_transform_sync_system);
         app.add_system(player_movement_system);
-
-        app.add_system_set(
-            SystemSet::new()
-                .with_run_criteria(
-                    FixedTimestep::step(TIMESTEP_60_FRAMES_PER_SECOND).with_label(TIMESTEP_LABEL),
-                )
-                .with_system(player_collider_system),
-        );
-    }
-}
-
-fn player_transform_sync_system(
-    mut players: Query<
-        (&mut GlobalTransform, &Quad2d),
-        (With<GlobalTransform>, Changed<Quad2d>, With<Player>),
-    >,
-) {
-    for (mut transform, quad) in players.iter_mut() {
-        if transform.translation.x != quad.position.x {
-            transform.translation.x = quad.position.x;
-        }
-        if transform.translation.y != quad.position.y {
-            transform.translation.y = quad.position.y;
-        }
+        app.add_system_to_stage(PhysicsStage, player_collider_system.after(RIGID_BODY_LABEL));
     }
 }
 
 fn player_movement_system(
     keyboard_input: Res<Input<KeyCode>>,
+    config: Res<PhysicsConfig>,
     mut player: Query<
-        (&mut PlayerState, &mut RigidBody),
+        (&mut PlayerState, &mut RigidBody, Option<&PhysicsOverrides>),
         (With<PlayerState>, With<RigidBody>, With<Player>),
     >,
 ) {
     if player.is_empty() {
         return;
     }
-    let (mut state, mut body) = player.single_mut();
+    let (mut state, mut body, overrides) = player.single_mut();
+    let config = config.with_overrides(overrides);
 
     if keyboard_input.pressed(KeyCode::Left) {
-        body.acceleration.x = -MOVEMENT_SPEED;
+        body.acceleration.x = -config.movement_speed;
     }
     if keyboard_input.pressed(KeyCode::Right) {
-        body.acceleration.x = MOVEMENT_SPEED;
+        body.acceleration.x = config.movement_speed;
     }
 
     if !keyboard_input.pressed(KeyCode::Left) && !keyboard_input.pressed(KeyCode::Right) {
@@ -83,14 +67,19 @@ fn player_movement_system(
 
     if keyboard_input.pressed(KeyCode::LAlt) && state.ground {
         state.ground = false;
-        body.acceleration.y = JUMP_FORCE;
+        body.acceleration.y = config.jump_force;
     }
 }
 
-const COLLISION_THRESHOLD: f32 = 4.0;
+/// Height of foothold edges and joints that are stepped over instead of blocking.
+const MAX_STEP_HEIGHT: f32 = 4.0;
+
+/// Distance kept between a quad and a wall it was stopped by.
+const WALL_SKIN: f32 = 0.01;
 
 fn player_collider_system(
     mut commands: Commands,
+    config: Res<PhysicsConfig>,
     mut player: Query<
         (
             Entity,
@@ -98,6 +87,9 @@ fn player_collider_system(
             &mut Quad2d,
             &mut RigidBody,
             Option<&FootholdLayer>,
+            Option<&CollisionGroups>,
+            Option<&TiltToSlope>,
+            Option<&PhysicsOverrides>,
         ),
         (
             With<PlayerState>,
@@ -106,24 +98,57 @@ fn player_collider_system(
             With<Player>,
         ),
     >,
-    mut footholds: Query<(&Foothold, &FootholdLayer), (With<Foothold>, With<FootholdLayer>)>,
+    footholds: Query<
+        (&Foothold, &FootholdLayer, Option<&CollisionGroups>),
+        (With<Foothold>, With<FootholdLayer>),
+    >,
 ) {
     if player.is_empty() {
         return;
     }
-    let (entity, mut state, mut current, mut body, layer) = player.single_mut();
+    let (entity, mut state, mut current, mut body, layer, groups, tilt, overrides) =
+        player.single_mut();
+    let config = config.with_overrides(overrides);
+
+    // Only footholds the player's groups interact with are solid
+    let groups = groups.copied().unwrap_or_default();
+    let footholds = footholds
+        .iter()
+        .filter(|(_, _, foothold_groups)| {
+            groups.interacts_with(
+                &foothold_groups
+                    .copied()
+                    .unwrap_or_else(CollisionGroups::foothold),
+            )
+        })
+        .map(|(foothold, foothold_layer, _)| (foothold, foothold_layer))
+        .collect::<Vec<_>>();
 
     // Calculate the next position
+    let dt = PHYSICS_TIMESTEP as f32;
     let mut next = current.clone();
-    next.position += Vec2::new(body.velocity.x, body.velocity.y)
-        + Vec2::new(0.5 * body.acceleration.x, 0.5 * body.acceleration.y);
+    next.position += (body.velocity * dt) + (0.5 * body.acceleration * dt * dt);
 
-    // Get the anchor points
+    // Wall collision logic: stop at the first wall in the way
+    let delta = next.position - current.position;
+    if let Some(hit) = footholds
+        .iter()
+        .filter(|(_, foothold_layer)| layer.map_or(true, |layer| foothold_layer.0 == layer.0))
+        .filter_map(|(foothold, _)| calculate_fh_wall_collision(foothold, &current, delta))
+        .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal))
+    {
+        next.position.x = current.position.x + (delta.x * hit.time) + (hit.normal.x * WALL_SKIN);
+        body.velocity.x = 0.0;
+        body.acceleration.x = 0.0;
+    }
+
+    // Get the anchor point and its movement
     let current_anchor = quad_anchor_point(&current);
-    let next_anchor = quad_anchor_point(&next);
+    let anchor_delta = next.position - current.position;
 
     // Keep track of collisions here
     let mut collisions = 0;
+    let mut ground_angle = None;
 
     // Check if there is an existing layer
     if layer.is_some() {
@@ -131,14 +156,17 @@ fn player_collider_system(
 
         // Foothold collision logic
         for (foothold, _) in footholds
-            .iter_mut()
+            .iter()
             .filter(|(_, foothold_layer)| foothold_layer.0 == layer.0)
         {
-            if let Some(collision) =
-                calculate_fh_collision(&foothold, &foothold, current_anchor, next_anchor)
+            if let Some(collision) = calculate_fh_collision(foothold, current_anchor, anchor_delta)
             {
                 state.ground = true;
-                quad_set_pos_from_anchor_point(&mut next, None, Some(collision.y));
+                let y = foothold
+                    .get_y_at_x(next.position.x)
+                    .unwrap_or(collision.point.y);
+                quad_set_pos_from_anchor_point(&mut next, None, Some(y));
+                ground_angle = Some(calculate_fh_angle(foothold, next.position));
                 collisions += 1; // Collision found in this layer
             }
         }
@@ -152,50 +180,71 @@ fn player_collider_system(
     // Check if there was a collision in the existing layer
     if collisions == 0 {
         // Foothold collision logic
-        for (foothold, foothold_layer) in footholds.iter_mut() {
-            if let Some(collision) =
-                calculate_fh_collision(&foothold, &foothold, current_anchor, next_anchor)
+        for &(foothold, foothold_layer) in footholds.iter() {
+            if let Some(collision) = calculate_fh_collision(foothold, current_anchor, anchor_delta)
             {
                 state.ground = true;
-                quad_set_pos_from_anchor_point(&mut next, None, Some(collision.y));
+                let y = foothold
+                    .get_y_at_x(next.position.x)
+                    .unwrap_or(collision.point.y);
+                quad_set_pos_from_anchor_point(&mut next, None, Some(y));
 
                 commands.entity(entity).insert(foothold_layer.clone());
 
                 // Apply angular force
                 let angle = calculate_fh_angle(foothold, next.position);
+                ground_angle = Some(angle);
                 if angle != 0.0 {
-                    body.acceleration.x += GRAVITY / angle.tan();
-                    body.acceleration.y += GRAVITY;
+                    body.acceleration.x += config.gravity * dt / angle.tan();
+                    body.acceleration.y += config.gravity * dt;
                 }
             }
         }
     }
 
+    // Tilt to the ground, or straighten up in the air
+    if tilt.is_some() {
+        next.rotation = ground_angle.unwrap_or(0.0);
+    }
+
     // Finally, update the player's position (Only update if changed)
     if current.position != next.position {
         current.position = next.position;
     }
+    if current.rotation != next.rotation {
+        current.rotation = next.rotation;
+    }
 }
 
-/// Calculate any collisions for a foothold, using the current and next points
-fn calculate_fh_collision(
-    current_fh: &Foothold,
-    next_fh: &Foothold,
-    current: Vec2,
-    next: Vec2,
-) -> Option<Vec2> {
-    // Get the foothold y position for current and next points
-    if let (Some(current_fh_y), Some(next_fh_y)) =
-        (current_fh.get_y_at_x(current.x), next_fh.get_y_at_x(next.x))
-    {
-        // Important: Use this threshold to check for realistic changes in y
-        if (current_fh_y - next_fh_y).abs() < COLLISION_THRESHOLD {
-            if current.y >= current_fh_y && next.y <= next_fh_y {
-                return Some(Vec2::new(next.x, next_fh_y));
-            }
-        }
-    }
-    None
+/// Calculate the first landing on a foothold, sweeping the anchor point along its movement
+fn calculate_fh_collision(foothold: &Foothold, anchor: Vec2, delta: Vec2) -> Option<SweepHit> {
+    foothold_lines(foothold)
+        .filter_map(|line| sweep_point_line(anchor, delta, &line))
+        .filter(|hit| hit.normal.y >= FLOOR_NORMAL_Y)
+        .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal))
+}
+
+/// Calculate the first wall hit on a foothold, sweeping the whole quad along its movement
+fn calculate_fh_wall_collision(
+    foothold: &Foothold,
+    quad: &Quad2d,
+    delta: Vec2,
+) -> Option<SweepHit> {
+    let step_y = quad.mid_bottom().y + MAX_STEP_HEIGHT;
+
+    foothold_lines(foothold)
+        .filter(|line| line.p1.y.max(line.p2.y) > step_y)
+        .filter_map(|line| sweep_quad_line(quad, delta, &line))
+        .filter(|hit| hit.normal.y.abs() < FLOOR_NORMAL_Y)
+        .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal))
+}
+
+/// Iterate over the segments of a foothold as lines
+fn foothold_lines(foothold: &Foothold) -> impl Iterator<Item = Line2d> + '_ {
+    foothold
+        .segments()
+        .iter()
+        .map(|segment| Line2d::from_points(segment.start, segment.end))
 }
 
 /// Calculate the angle for a given foothold, using the current point
@@ -214,10 +263,12 @@ fn quad_anchor_point(quad: &Quad2d) -> Vec2 {
 
 /// Set the position using the anchor point for the quad
 fn quad_set_pos_from_anchor_point(quad: &mut Quad2d, x: Option<f32>, y: Option<f32>) {
+    // Offset of the anchor from the center, which depends on the rotation
+    let offset = quad_anchor_point(quad) - quad.position;
     if x.is_some() {
-        quad.position.x = x.unwrap();
+        quad.position.x = x.unwrap() - offset.x;
     }
     if y.is_some() {
-        quad.position.y = (y.unwrap() + (quad.height / 2.)).ceil();
+        quad.position.y = (y.unwrap() - offset.y).ceil();
     }
 }
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use super::{adapter::shape_component, quad::*};
+
+/// Represents a convex polygon with points relative to its position
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct ConvexPolygon2d(pub geometry::ConvexPolygon2d);
+
+shape_component!(ConvexPolygon2d, geometry::ConvexPolygon2d);
+
+impl ConvexPolygon2d {
+    /// Creates a new polygon at (x, y) from points relative to it.
+    /// The points must form a convex shape, and are stored counter-clockwise.
+    pub fn new(x: f32, y: f32, points: &[Vec2]) -> Self {
+        Self(geometry::ConvexPolygon2d::new(x, y, points))
+    }
+}
+
+impl From<&Quad2d> for ConvexPolygon2d {
+    fn from(quad: &Quad2d) -> Self {
+        Self(geometry::ConvexPolygon2d::from(&quad.0))
+    }
+}
@@ -1,63 +1,49 @@
 use bevy::prelude::*;
 
-/// Represents a line with 2 points
+use super::adapter::shape_component;
+
+/// Represents a rectangle centered at a position, rotated around it
 #[derive(Debug, Clone, Component)]
-pub struct Quad2d {
-    pub position: Vec2,
-    pub width: f32,
-    pub height: f32,
-}
+pub struct Quad2d(pub geometry::Quad2d);
+
+shape_component!(Quad2d, geometry::Quad2d);
 
 impl Quad2d {
     pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
-        Self {
-            position: Vec2::new(x, y),
-            width,
-            height,
-        }
+        Self(geometry::Quad2d::new(x, y, width, height))
     }
 
-    pub fn top_left(&self) -> Vec2 {
-        Vec2::new(
-            self.position.x - (self.width / 2.0),
-            self.position.y + (self.height / 2.0),
-        )
+    /// Sets the counter-clockwise rotation in radians.
+    pub fn with_rotation(self, rotation: f32) -> Self {
+        Self(self.0.with_rotation(rotation))
     }
 
-    pub fn bottom_left(&self) -> Vec2 {
-        Vec2::new(
-            self.position.x - (self.width / 2.0),
-            self.position.y - (self.height / 2.0),
-        )
+    /// Creates an unrotated quad from its min and max corners
+    pub fn from_min_max(min: Vec2, max: Vec2) -> Self {
+        Self(geometry::Quad2d::from_min_max(min, max))
     }
 
-    pub fn top_right(&self) -> Vec2 {
-        Vec2::new(
-            self.position.x + (self.width / 2.0),
-            self.position.y + (self.height / 2.0),
-        )
+    /// Creates an unrotated quad from any 2 opposite corners
+    pub fn from_corners(a: Vec2, b: Vec2) -> Self {
+        Self(geometry::Quad2d::from_corners(a, b))
     }
 
-    pub fn bottom_right(&self) -> Vec2 {
-        Vec2::new(
-            self.position.x + (self.width / 2.0),
-            self.position.y - (self.height / 2.0),
+    /// Creates a quad covering a sprite, using the image size unless the sprite has a custom size
+    ///
+    /// # Arguments
+    ///
+    /// * `sprite`: The sprite to cover.
+    /// * `transform`: The transform of the sprite, its scale and z rotation are applied.
+    /// * `image_size`: The size of the image of the sprite.
+    pub fn from_sprite(sprite: &Sprite, transform: &Transform, image_size: Vec2) -> Self {
+        let size = sprite.custom_size.unwrap_or(image_size) * transform.scale.truncate().abs();
+        let (axis, angle) = transform.rotation.to_axis_angle();
+        Self::new(
+            transform.translation.x,
+            transform.translation.y,
+            size.x,
+            size.y,
         )
-    }
-
-    pub fn mid_top(&self) -> Vec2 {
-        Vec2::new(self.position.x, self.position.y + (self.height / 2.0))
-    }
-
-    pub fn mid_bottom(&self) -> Vec2 {
-        Vec2::new(self.position.x, self.position.y - (self.height / 2.0))
-    }
-
-    pub fn mid_left(&self) -> Vec2 {
-        Vec2::new(self.position.x - (self.width / 2.0), self.position.y)
-    }
-
-    pub fn mid_right(&self) -> Vec2 {
-        Vec2::new(self.position.x + (self.width / 2.0), self.position.y)
+        .with_rotation(angle * axis.z)
     }
 }
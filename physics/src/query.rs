@@ -0,0 +1,650 @@
+use std::cmp::Ordering;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::{
+    capsule::*, circle::*, collider::*, foothold::*, groups::*, line::*, polygon::*, quad::*,
+};
+
+/// Represents which entities a spatial query can hit.
+#[derive(Clone, Debug)]
+pub struct QueryFilter {
+    /// Entities that are never hit, usually the one asking.
+    pub exclude: Vec<Entity>,
+    /// Whether footholds can be hit.
+    pub footholds: bool,
+    /// Whether collider shapes can be hit.
+    pub colliders: bool,
+    /// Only footholds in this layer can be hit.
+    pub layer: Option<u32>,
+    /// Groups of the query, only entities that interact with them can be hit.
+    pub groups: CollisionGroups,
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self {
+            exclude: Vec::new(),
+            footholds: true,
+            colliders: true,
+            layer: None,
+            groups: CollisionGroups::default(),
+        }
+    }
+}
+
+impl QueryFilter {
+    /// Never hit an entity.
+    pub fn exclude(mut self, entity: Entity) -> Self {
+        self.exclude.push(entity);
+        self
+    }
+
+    /// Only hit footholds.
+    pub fn only_footholds(mut self) -> Self {
+        self.footholds = true;
+        self.colliders = false;
+        self
+    }
+
+    /// Only hit collider shapes.
+    pub fn only_colliders(mut self) -> Self {
+        self.footholds = false;
+        self.colliders = true;
+        self
+    }
+
+    /// Only hit footholds in a layer.
+    pub fn with_layer(mut self, layer: u32) -> Self {
+        self.layer = Some(layer);
+        self
+    }
+
+    /// Only hit entities that interact with these groups.
+    pub fn with_groups(mut self, groups: CollisionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Returns true if an entity can be hit, using its groups or the default groups.
+    pub fn allows(&self, entity: Entity, groups: Option<&CollisionGroups>) -> bool {
+        !self.exclude.contains(&entity)
+            && self
+                .groups
+                .interacts_with(groups.unwrap_or(&CollisionGroups::default()))
+    }
+
+    /// Returns true if a foothold can be hit, using its groups or the foothold groups.
+    fn allows_foothold(
+        &self,
+        entity: Entity,
+        layer: Option<&FootholdLayer>,
+        groups: Option<&CollisionGroups>,
+    ) -> bool {
+        let groups = groups.copied().unwrap_or_else(CollisionGroups::foothold);
+        self.allows(entity, Some(&groups)) && self.allows_layer(layer)
+    }
+
+    fn allows_layer(&self, layer: Option<&FootholdLayer>) -> bool {
+        match (self.layer, layer) {
+            (Some(filter), Some(layer)) => filter == layer.0,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Represents the first thing a ray hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    /// Index of the segment that was hit within the foothold, if the entity is a foothold.
+    pub segment: Option<usize>,
+    pub point: Vec2,
+    /// Unit normal of the surface that was hit, facing against the ray.
+    pub normal: Vec2,
+    /// Distance from the origin to the point.
+    pub distance: f32,
+}
+
+/// Represents a shape that can be swept through the world.
+#[derive(Clone, Debug)]
+pub enum CastShape {
+    Quad(Quad2d),
+    Polygon(ConvexPolygon2d),
+    Circle(Circle2d),
+    Capsule(Capsule2d),
+}
+
+impl CastShape {
+    /// Gets a copy of this shape moved to a position
+    fn at(&self, position: Vec2) -> Self {
+        let mut shape = self.clone();
+        match &mut shape {
+            CastShape::Quad(quad) => quad.position = position,
+            CastShape::Polygon(polygon) => polygon.position = position,
+            CastShape::Circle(circle) => circle.position = position,
+            CastShape::Capsule(capsule) => capsule.position = position,
+        }
+        shape
+    }
+
+    /// Gets the center of this shape
+    fn center(&self) -> Vec2 {
+        match self {
+            CastShape::Quad(quad) => quad.position,
+            CastShape::Polygon(polygon) => {
+                let points = polygon.world_points();
+                points.iter().sum::<Vec2>() / points.len().max(1) as f32
+            }
+            CastShape::Circle(circle) => circle.position,
+            CastShape::Capsule(capsule) => capsule.position,
+        }
+    }
+
+    /// Sweeps this shape by `delta` against a line
+    fn sweep_line(&self, delta: Vec2, line: &Line2d) -> Option<SweepHit> {
+        match self {
+            CastShape::Quad(quad) => sweep_quad_line(quad, delta, line),
+            CastShape::Polygon(polygon) => sweep_polygon_line(polygon, delta, line),
+            CastShape::Circle(circle) => sweep_circle_line(circle, delta, line),
+            CastShape::Capsule(capsule) => sweep_capsule_line(capsule, delta, line),
+        }
+    }
+
+    /// Sweeps this shape by `delta` against a polygon
+    fn sweep_polygon(&self, delta: Vec2, other: &ConvexPolygon2d) -> Option<SweepHit> {
+        match self {
+            CastShape::Quad(quad) => {
+                sweep_polygon_polygon(&ConvexPolygon2d::from(quad), delta, other)
+            }
+            CastShape::Polygon(polygon) => sweep_polygon_polygon(polygon, delta, other),
+            CastShape::Circle(circle) => sweep_circle_polygon(circle, delta, other),
+            CastShape::Capsule(capsule) => sweep_capsule_polygon(capsule, delta, other),
+        }
+    }
+
+    /// Sweeps this shape by `delta` against a circle
+    fn sweep_circle(&self, delta: Vec2, other: &Circle2d) -> Option<SweepHit> {
+        match self {
+            CastShape::Quad(quad) => {
+                sweep_circle_polygon(other, -delta, &ConvexPolygon2d::from(quad))
+                    .map(|hit| reverse_sweep_hit(hit, delta))
+            }
+            CastShape::Polygon(polygon) => sweep_circle_polygon(other, -delta, polygon)
+                .map(|hit| reverse_sweep_hit(hit, delta)),
+            CastShape::Circle(circle) => sweep_circle_circle(circle, delta, other),
+            CastShape::Capsule(capsule) => sweep_circle_capsule(other, -delta, capsule)
+                .map(|hit| reverse_sweep_hit(hit, delta)),
+        }
+    }
+
+    /// Sweeps this shape by `delta` against a capsule
+    fn sweep_capsule(&self, delta: Vec2, other: &Capsule2d) -> Option<SweepHit> {
+        match self {
+            CastShape::Quad(quad) => {
+                sweep_capsule_polygon(other, -delta, &ConvexPolygon2d::from(quad))
+                    .map(|hit| reverse_sweep_hit(hit, delta))
+            }
+            CastShape::Polygon(polygon) => sweep_capsule_polygon(other, -delta, polygon)
+                .map(|hit| reverse_sweep_hit(hit, delta)),
+            CastShape::Circle(circle) => sweep_circle_capsule(circle, delta, other),
+            CastShape::Capsule(capsule) => sweep_capsule_capsule(capsule, delta, other),
+        }
+    }
+}
+
+impl From<Quad2d> for CastShape {
+    fn from(quad: Quad2d) -> Self {
+        CastShape::Quad(quad)
+    }
+}
+
+impl From<ConvexPolygon2d> for CastShape {
+    fn from(polygon: ConvexPolygon2d) -> Self {
+        CastShape::Polygon(polygon)
+    }
+}
+
+impl From<Circle2d> for CastShape {
+    fn from(circle: Circle2d) -> Self {
+        CastShape::Circle(circle)
+    }
+}
+
+impl From<Capsule2d> for CastShape {
+    fn from(capsule: Capsule2d) -> Self {
+        CastShape::Capsule(capsule)
+    }
+}
+
+/// Represents the first thing a swept shape hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeHit {
+    pub entity: Entity,
+    /// Index of the segment that was hit within the foothold, if the entity is a foothold.
+    pub segment: Option<usize>,
+    /// Fraction of the movement completed at the contact, from 0.0 to 1.0.
+    pub time: f32,
+    pub point: Vec2,
+    /// Unit normal of the surface that was hit, facing against the movement.
+    pub normal: Vec2,
+}
+
+/// Represents a foothold segment a quad is touching.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FootholdContact {
+    pub entity: Entity,
+    /// Index of the segment that is touched within the foothold.
+    pub segment: usize,
+    pub contact: Contact,
+}
+
+/// System parameter for querying footholds and colliders in the world.
+///
+/// # Examples
+/// ```
+/// fn line_of_sight_system(query: SpatialQuery, mobs: Query<(Entity, &Quad2d)>) {
+///     let filter = QueryFilter::default().only_footholds();
+///     if let Some(hit) = query.raycast(origin, Vec2::X, 500.0, &filter) {
+///         println!("blocked at {}", hit.point);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+    footholds: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Foothold,
+            Option<&'static FootholdLayer>,
+            Option<&'static CollisionGroups>,
+        ),
+    >,
+    quads: Query<'w, 's, (Entity, &'static Quad2d, Option<&'static CollisionGroups>)>,
+    polygons: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static ConvexPolygon2d,
+            Option<&'static CollisionGroups>,
+        ),
+    >,
+    circles: Query<'w, 's, (Entity, &'static Circle2d, Option<&'static CollisionGroups>)>,
+    capsules: Query<'w, 's, (Entity, &'static Capsule2d, Option<&'static CollisionGroups>)>,
+}
+
+impl<'w, 's> SpatialQuery<'w, 's> {
+    /// Casts a ray and returns the closest hit.
+    /// Rays starting inside a collider hit it at distance 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`: The start of the ray.
+    /// * `dir`: The direction of the ray, does not need to be normalized.
+    /// * `max_dist`: The length of the ray.
+    /// * `filter`: The entities the ray can hit.
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        dir: Vec2,
+        max_dist: f32,
+        filter: &QueryFilter,
+    ) -> Option<RayHit> {
+        let dir = dir.normalize_or_zero();
+        if dir == Vec2::ZERO || max_dist <= 0.0 {
+            return None;
+        }
+        let delta = dir * max_dist;
+
+        let mut hits: Vec<(Entity, Option<usize>, SweepHit)> = Vec::new();
+
+        if filter.footholds {
+            for (entity, foothold, layer, groups) in self.footholds.iter() {
+                if !filter.allows_foothold(entity, layer, groups) {
+                    continue;
+                }
+                for (id, segment) in foothold.segments().iter().enumerate() {
+                    let line = Line2d::from_points(segment.start, segment.end);
+                    if let Some(hit) = sweep_point_line(origin, delta, &line) {
+                        hits.push((entity, Some(id), hit));
+                    }
+                }
+            }
+        }
+
+        if filter.colliders {
+            for (entity, quad, _) in self
+                .quads
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = sweep_point_quad(origin, delta, quad) {
+                    hits.push((entity, None, hit));
+                }
+            }
+            for (entity, polygon, _) in self
+                .polygons
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = sweep_point_polygon(origin, delta, polygon) {
+                    hits.push((entity, None, hit));
+                }
+            }
+            for (entity, circle, _) in self
+                .circles
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = sweep_point_circle(origin, delta, circle) {
+                    hits.push((entity, None, hit));
+                }
+            }
+            for (entity, capsule, _) in self
+                .capsules
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = sweep_point_capsule(origin, delta, capsule) {
+                    hits.push((entity, None, hit));
+                }
+            }
+        }
+
+        hits.into_iter()
+            .min_by(|a, b| a.2.time.partial_cmp(&b.2.time).unwrap_or(Ordering::Equal))
+            .map(|(entity, segment, hit)| RayHit {
+                entity,
+                segment,
+                point: hit.point,
+                normal: hit.normal,
+                distance: hit.time * max_dist,
+            })
+    }
+
+    /// Sweeps a shape from one position to another and returns the first hit.
+    /// Surfaces the shape is already touching and moving away from are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape`: The shape to sweep, its own position is ignored.
+    /// * `from`: The position the shape starts at.
+    /// * `to`: The position the shape moves to.
+    /// * `filter`: The entities the shape can hit.
+    ///
+    /// # Examples
+    /// ```
+    /// let filter = QueryFilter::default().exclude(player);
+    /// let to = quad.position + Vec2::new(200.0, 0.0);
+    /// match query.shape_cast(quad.clone(), quad.position, to, &filter) {
+    ///     Some(hit) => quad.position = quad.position.lerp(to, hit.time),
+    ///     None => quad.position = to,
+    /// };
+    /// ```
+    pub fn shape_cast(
+        &self,
+        shape: impl Into<CastShape>,
+        from: Vec2,
+        to: Vec2,
+        filter: &QueryFilter,
+    ) -> Option<ShapeHit> {
+        let shape = shape.into().at(from);
+        let delta = to - from;
+        if delta.length() < EPSILON {
+            return None;
+        }
+
+        let mut hits: Vec<(Entity, Option<usize>, SweepHit)> = Vec::new();
+
+        if filter.footholds {
+            for (entity, foothold, layer, groups) in self.footholds.iter() {
+                if !filter.allows_foothold(entity, layer, groups) {
+                    continue;
+                }
+                for (id, segment) in foothold.segments().iter().enumerate() {
+                    let line = Line2d::from_points(segment.start, segment.end);
+                    if let Some(hit) = shape.sweep_line(delta, &line) {
+                        hits.push((entity, Some(id), hit));
+                    }
+                }
+            }
+        }
+
+        if filter.colliders {
+            for (entity, quad, _) in self
+                .quads
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = shape.sweep_polygon(delta, &ConvexPolygon2d::from(quad)) {
+                    hits.push((entity, None, hit));
+                }
+            }
+            for (entity, polygon, _) in self
+                .polygons
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = shape.sweep_polygon(delta, polygon) {
+                    hits.push((entity, None, hit));
+                }
+            }
+            for (entity, circle, _) in self
+                .circles
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = shape.sweep_circle(delta, circle) {
+                    hits.push((entity, None, hit));
+                }
+            }
+            for (entity, capsule, _) in self
+                .capsules
+                .iter()
+                .filter(|(it, _, groups)| filter.allows(*it, *groups))
+            {
+                if let Some(hit) = shape.sweep_capsule(delta, capsule) {
+                    hits.push((entity, None, hit));
+                }
+            }
+        }
+
+        // A real contact has the shape on the side the normal faces
+        let center = shape.center();
+        hits.into_iter()
+            .filter(|(_, _, hit)| (center + delta * hit.time - hit.point).dot(hit.normal) >= 0.0)
+            .min_by(|a, b| a.2.time.partial_cmp(&b.2.time).unwrap_or(Ordering::Equal))
+            .map(|(entity, segment, hit)| ShapeHit {
+                entity,
+                segment,
+                time: hit.time,
+                point: hit.point,
+                normal: hit.normal,
+            })
+    }
+
+    /// Gets every foothold segment a quad is touching, with the contact for each.
+    ///
+    /// # Arguments
+    ///
+    /// * `quad`: The quad to test.
+    /// * `filter`: The footholds the quad can touch, colliders are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// for hit in query.foothold_contacts(&quad, &QueryFilter::default()) {
+    ///     if hit.contact.is_floor() {
+    ///         println!("standing on segment {} of {:?}", hit.segment, hit.entity);
+    ///     }
+    /// }
+    /// ```
+    pub fn foothold_contacts(&self, quad: &Quad2d, filter: &QueryFilter) -> Vec<FootholdContact> {
+        let mut contacts = Vec::new();
+        if !filter.footholds {
+            return contacts;
+        }
+
+        for (entity, foothold, layer, groups) in self.footholds.iter() {
+            if !filter.allows_foothold(entity, layer, groups) {
+                continue;
+            }
+            for (id, segment) in foothold.segments().iter().enumerate() {
+                let line = Line2d::from_points(segment.start, segment.end);
+                if let Some(contact) = collide_quad_line(quad, &line) {
+                    contacts.push(FootholdContact {
+                        entity,
+                        segment: id,
+                        contact,
+                    });
+                }
+            }
+        }
+        contacts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+
+    /// Spawns a floor at y = 0 in layer 0, a floor at y = -50 in layer 1,
+    /// and a circle collider above the first floor.
+    fn world() -> (World, Entity, Entity, Entity) {
+        let mut world = World::default();
+        let top = world
+            .spawn()
+            .insert(Foothold::from_points(&[
+                Vec2::new(-100.0, 0.0),
+                Vec2::new(100.0, 0.0),
+            ]))
+            .insert(FootholdLayer(0))
+            .id();
+        let bottom = world
+            .spawn()
+            .insert(Foothold::from_points(&[
+                Vec2::new(-100.0, -40.0),
+                Vec2::new(-20.0, -50.0),
+                Vec2::new(100.0, -50.0),
+            ]))
+            .insert(FootholdLayer(1))
+            .id();
+        let collider = world.spawn().insert(Circle2d::new(0.0, 30.0, 5.0)).id();
+        (world, top, bottom, collider)
+    }
+
+    /// Drops a circle of radius 5 from y = 100 to y = -100.
+    fn drop_circle(world: &mut World, filter: &QueryFilter) -> Option<ShapeHit> {
+        let mut state = SystemState::<SpatialQuery<'static, 'static>>::new(world);
+        let query = state.get_mut(world);
+        query.shape_cast(
+            Circle2d::new(0.0, 0.0, 5.0),
+            Vec2::new(0.0, 100.0),
+            Vec2::new(0.0, -100.0),
+            filter,
+        )
+    }
+
+    fn assert_time(hit: &ShapeHit, time: f32) {
+        assert!(
+            (hit.time - time).abs() < 1e-4,
+            "expected time {}, got {}",
+            time,
+            hit.time
+        );
+    }
+
+    #[test]
+    fn shape_cast_hits_closest() {
+        let (mut world, _, _, collider) = world();
+
+        let hit = drop_circle(&mut world, &QueryFilter::default()).unwrap();
+        assert_eq!(hit.entity, collider);
+        assert_eq!(hit.segment, None);
+        // Centers 10 apart at y = 40
+        assert_time(&hit, 0.3);
+        assert!(hit.normal.distance(Vec2::Y) < 1e-4);
+    }
+
+    #[test]
+    fn shape_cast_reports_foothold_segment() {
+        let (mut world, top, bottom, collider) = world();
+
+        let hit = drop_circle(&mut world, &QueryFilter::default().exclude(collider)).unwrap();
+        assert_eq!(hit.entity, top);
+        assert_eq!(hit.segment, Some(0));
+        assert_time(&hit, 0.475);
+
+        let filter = QueryFilter::default().exclude(collider).exclude(top);
+        let hit = drop_circle(&mut world, &filter).unwrap();
+        assert_eq!(hit.entity, bottom);
+        assert_eq!(hit.segment, Some(1));
+        assert_time(&hit, 0.725);
+    }
+
+    #[test]
+    fn shape_cast_filters_kinds() {
+        let (mut world, top, _, collider) = world();
+
+        let hit = drop_circle(&mut world, &QueryFilter::default().only_footholds()).unwrap();
+        assert_eq!(hit.entity, top);
+
+        let filter = QueryFilter::default().only_colliders().exclude(collider);
+        assert_eq!(drop_circle(&mut world, &filter), None);
+    }
+
+    #[test]
+    fn shape_cast_filters_layers() {
+        let (mut world, _, bottom, _) = world();
+
+        let filter = QueryFilter::default().only_footholds().with_layer(1);
+        let hit = drop_circle(&mut world, &filter).unwrap();
+        assert_eq!(hit.entity, bottom);
+
+        // Footholds without a layer never match a layer filter
+        world.entity_mut(bottom).remove::<FootholdLayer>();
+        assert_eq!(drop_circle(&mut world, &filter), None);
+    }
+
+    #[test]
+    fn shape_cast_filters_groups() {
+        let (mut world, top, bottom, collider) = world();
+
+        // Ghosts go through footholds but still hit colliders
+        let ghost = CollisionGroups::new(GROUP_MOB, GROUP_ALL & !GROUP_FOOTHOLD);
+        let filter = QueryFilter::default().with_groups(ghost);
+        assert_eq!(drop_circle(&mut world, &filter).unwrap().entity, collider);
+        assert_eq!(drop_circle(&mut world, &filter.exclude(collider)), None);
+
+        // A foothold only solid for mobs lets players through to the next one
+        world
+            .entity_mut(top)
+            .insert(CollisionGroups::new(GROUP_FOOTHOLD, GROUP_MOB));
+        let player = CollisionGroups::new(GROUP_PLAYER, GROUP_ALL);
+        let filter = QueryFilter::default().only_footholds().with_groups(player);
+        assert_eq!(drop_circle(&mut world, &filter).unwrap().entity, bottom);
+
+        let mob = CollisionGroups::new(GROUP_MOB, GROUP_ALL);
+        let filter = QueryFilter::default().only_footholds().with_groups(mob);
+        assert_eq!(drop_circle(&mut world, &filter).unwrap().entity, top);
+    }
+
+    #[test]
+    fn shape_cast_without_movement() {
+        let (mut world, _, _, _) = world();
+
+        let mut state = SystemState::<SpatialQuery<'static, 'static>>::new(&mut world);
+        let query = state.get_mut(&mut world);
+        let circle = Circle2d::new(0.0, 0.0, 5.0);
+        let at = Vec2::new(0.0, 100.0);
+        assert_eq!(
+            query.shape_cast(circle, at, at, &QueryFilter::default()),
+            None
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use bevy::prelude::*;
 use bevy_prototype_debug_lines::*;
 
-use super::{foothold::*, line::*, quad::*};
+use super::{capsule::*, circle::*, foothold::*, line::*, polygon::*, quad::*, trigger::*};
 
 const DEFAULT_COLOR: Color = Color::BEIGE;
+const CIRCLE_SEGMENTS: usize = 16;
 // const DEFAULT_PALETTE: [Color; 10] = [
 //     Color::ALICE_BLUE,
 //     Color::ANTIQUE_WHITE,
@@ -46,6 +47,10 @@ impl Plugin for RenderPlugin {
         app.add_plugin(DebugLinesPlugin::default())
             .add_system(render_lines_system)
             .add_system(render_quads_system)
+            .add_system(render_polygons_system)
+            .add_system(render_trigger_zones_system)
+            .add_system(render_circles_system)
+            .add_system(render_capsules_system)
             .add_system(render_footholds_system);
     }
 }
@@ -73,13 +78,89 @@ fn render_quads_system(mut debug_lines: ResMut<DebugLines>, quads: Query<(&Quad2
     }
 }
 
+fn render_polygons_system(
+    mut debug_lines: ResMut<DebugLines>,
+    polygons: Query<(&ConvexPolygon2d, &RenderColor)>,
+) {
+    for (polygon, render_color) in polygons.iter() {
+        let color = render_color.color;
+        for (p1, p2) in polygon.edges() {
+            debug_lines.line_colored(p1.extend(0.0), p2.extend(0.0), 0., color);
+        }
+    }
+}
+
+fn render_trigger_zones_system(
+    mut debug_lines: ResMut<DebugLines>,
+    zones: Query<(&TriggerZone, &RenderColor)>,
+) {
+    for (zone, render_color) in zones.iter() {
+        let color = render_color.color;
+        for (p1, p2) in zone.region.edges() {
+            debug_lines.line_colored(p1.extend(0.0), p2.extend(0.0), 0., color);
+        }
+    }
+}
+
+fn render_circles_system(
+    mut debug_lines: ResMut<DebugLines>,
+    circles: Query<(&Circle2d, &RenderColor)>,
+) {
+    for (circle, render_color) in circles.iter() {
+        render_arc(
+            &mut debug_lines,
+            circle.position,
+            circle.radius,
+            0.0,
+            1.0,
+            render_color.color,
+        );
+    }
+}
+
+fn render_capsules_system(
+    mut debug_lines: ResMut<DebugLines>,
+    capsules: Query<(&Capsule2d, &RenderColor)>,
+) {
+    for (capsule, render_color) in capsules.iter() {
+        let color = render_color.color;
+        let (top, bottom, radius) = (capsule.top(), capsule.bottom(), capsule.radius);
+
+        render_arc(&mut debug_lines, top, radius, 0.0, 0.5, color);
+        render_arc(&mut debug_lines, bottom, radius, 0.5, 0.5, color);
+
+        let side = Vec3::new(radius, 0.0, 0.0);
+        debug_lines.line_colored(top.extend(0.0) - side, bottom.extend(0.0) - side, 0., color);
+        debug_lines.line_colored(top.extend(0.0) + side, bottom.extend(0.0) + side, 0., color);
+    }
+}
+
+/// Draws part of a circle, starting at a fraction of a turn and spanning a fraction of a turn
+fn render_arc(
+    debug_lines: &mut DebugLines,
+    center: Vec2,
+    radius: f32,
+    start: f32,
+    span: f32,
+    color: Color,
+) {
+    let segments = ((CIRCLE_SEGMENTS as f32 * span).ceil() as usize).max(1);
+    let point = |it: usize| {
+        let angle = (start + span * it as f32 / segments as f32) * std::f32::consts::TAU;
+        (center + Vec2::new(angle.cos(), angle.sin()) * radius).extend(0.0)
+    };
+    for it in 1..=segments {
+        debug_lines.line_colored(point(it - 1), point(it), 0., color);
+    }
+}
+
 fn render_footholds_system(
     mut debug_lines: ResMut<DebugLines>,
     lines: Query<(&Foothold, &RenderColor)>,
 ) {
     for (foothold, render_color) in lines.iter() {
         let color = render_color.color;
-        let points = &foothold.points;
+        let points = foothold.points();
         for it in 1..points.len() {
             let p1 = points[it - 1];
             let p2 = points[it];
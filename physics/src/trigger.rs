@@ -0,0 +1,268 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use super::{capsule::*, circle::*, collider::*, groups::*, line::*, polygon::*, quad::*};
+
+/// Represents a region that reports colliders inside it, without blocking them.
+///
+/// The region is stored in the zone instead of as a collider component,
+/// so footholds, raycasts and the broadphase never see it.
+///
+/// # Examples
+/// ```
+/// commands
+///     .spawn()
+///     .insert(TriggerZone::from(Quad2d::new(300.0, 50.0, 100.0, 100.0)))
+///     .insert(Portal { map: 2 });
+/// ```
+#[derive(Clone, Component, Debug)]
+pub struct TriggerZone {
+    pub region: ConvexPolygon2d,
+    occupants: HashSet<Entity>,
+}
+
+impl TriggerZone {
+    /// Creates a zone covering a polygon.
+    pub fn new(region: ConvexPolygon2d) -> Self {
+        Self {
+            region,
+            occupants: HashSet::new(),
+        }
+    }
+
+    /// Iterates over the entities currently inside the zone.
+    pub fn occupants(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.occupants.iter().copied()
+    }
+
+    /// Returns true if the entity is currently inside the zone.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.occupants.contains(&entity)
+    }
+}
+
+impl From<Quad2d> for TriggerZone {
+    fn from(quad: Quad2d) -> Self {
+        TriggerZone::new(ConvexPolygon2d::from(&quad))
+    }
+}
+
+impl From<ConvexPolygon2d> for TriggerZone {
+    fn from(polygon: ConvexPolygon2d) -> Self {
+        TriggerZone::new(polygon)
+    }
+}
+
+/// Event sent when a collider enters a zone.
+#[derive(Clone, Debug)]
+pub struct ZoneEntered {
+    pub zone: Entity,
+    pub entity: Entity,
+}
+
+/// Event sent when a collider leaves a zone, or stops existing while inside it.
+#[derive(Clone, Debug)]
+pub struct ZoneExited {
+    pub zone: Entity,
+    pub entity: Entity,
+}
+
+/// Plugin for trigger zones.
+pub struct TriggerPlugin;
+
+impl Plugin for TriggerPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<ZoneEntered>()
+            .add_event::<ZoneExited>()
+            .add_system_to_stage(CoreStage::PostUpdate, trigger_zone_system);
+    }
+}
+
+/// System used for updating the occupants of every zone, after colliders have moved.
+fn trigger_zone_system(
+    mut zones: Query<(Entity, &mut TriggerZone, Option<&CollisionGroups>)>,
+    quads: Query<(Entity, &Quad2d, Option<&CollisionGroups>)>,
+    polygons: Query<(Entity, &ConvexPolygon2d, Option<&CollisionGroups>)>,
+    circles: Query<(Entity, &Circle2d, Option<&CollisionGroups>)>,
+    capsules: Query<(Entity, &Capsule2d, Option<&CollisionGroups>)>,
+    mut entered: EventWriter<ZoneEntered>,
+    mut exited: EventWriter<ZoneExited>,
+) {
+    for (zone, mut trigger, zone_groups) in zones.iter_mut() {
+        let zone_groups = zone_groups.copied().unwrap_or_default();
+        let allowed = |entity: Entity, groups: Option<&CollisionGroups>| {
+            entity != zone && zone_groups.interacts_with(&groups.copied().unwrap_or_default())
+        };
+        let region = &trigger.region;
+
+        let mut occupants = HashSet::new();
+        for (entity, quad, groups) in quads.iter() {
+            if allowed(entity, groups)
+                && collide_polygon_polygon(&ConvexPolygon2d::from(quad), region).is_some()
+            {
+                occupants.insert(entity);
+            }
+        }
+        for (entity, polygon, groups) in polygons.iter() {
+            if allowed(entity, groups) && collide_polygon_polygon(polygon, region).is_some() {
+                occupants.insert(entity);
+            }
+        }
+        for (entity, circle, groups) in circles.iter() {
+            if allowed(entity, groups) && circle_in_region(circle, region) {
+                occupants.insert(entity);
+            }
+        }
+        for (entity, capsule, groups) in capsules.iter() {
+            if allowed(entity, groups) && capsule_in_region(capsule, region) {
+                occupants.insert(entity);
+            }
+        }
+
+        for entity in occupants.difference(&trigger.occupants) {
+            entered.send(ZoneEntered {
+                zone,
+                entity: *entity,
+            });
+        }
+        for entity in trigger.occupants.difference(&occupants) {
+            exited.send(ZoneExited {
+                zone,
+                entity: *entity,
+            });
+        }
+
+        // Only write when the occupants change, so Changed<TriggerZone> means something
+        if trigger.occupants != occupants {
+            trigger.occupants = occupants;
+        }
+    }
+}
+
+/// Returns true if a circle overlaps a region
+fn circle_in_region(circle: &Circle2d, region: &ConvexPolygon2d) -> bool {
+    region.contains_point(circle.position)
+        || region
+            .edges()
+            .iter()
+            .any(|(p1, p2)| collide_circle_line(circle, &Line2d::from_points(*p1, *p2)).is_some())
+}
+
+/// Returns true if a capsule overlaps a region
+fn capsule_in_region(capsule: &Capsule2d, region: &ConvexPolygon2d) -> bool {
+    region.contains_point(capsule.position)
+        || region
+            .edges()
+            .iter()
+            .any(|(p1, p2)| collide_capsule_line(capsule, &Line2d::from_points(*p1, *p2)).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::app::Events;
+
+    use super::*;
+
+    /// Creates an app with a zone at the origin, and a circle far outside of it.
+    fn app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugin(TriggerPlugin);
+        let zone = app
+            .world
+            .spawn()
+            .insert(TriggerZone::from(Quad2d::new(0.0, 0.0, 100.0, 100.0)))
+            .id();
+        let circle = app
+            .world
+            .spawn()
+            .insert(Circle2d::new(500.0, 0.0, 5.0))
+            .id();
+        (app, zone, circle)
+    }
+
+    fn move_to(app: &mut App, entity: Entity, position: Vec2) {
+        app.world.get_mut::<Circle2d>(entity).unwrap().position = position;
+    }
+
+    fn entered(app: &mut App) -> Vec<(Entity, Entity)> {
+        let mut events = app.world.get_resource_mut::<Events<ZoneEntered>>().unwrap();
+        events
+            .drain()
+            .map(|event| (event.zone, event.entity))
+            .collect()
+    }
+
+    fn exited(app: &mut App) -> Vec<(Entity, Entity)> {
+        let mut events = app.world.get_resource_mut::<Events<ZoneExited>>().unwrap();
+        events
+            .drain()
+            .map(|event| (event.zone, event.entity))
+            .collect()
+    }
+
+    fn contains(app: &App, zone: Entity, entity: Entity) -> bool {
+        app.world.get::<TriggerZone>(zone).unwrap().contains(entity)
+    }
+
+    #[test]
+    fn enter_and_leave() {
+        let (mut app, zone, circle) = app();
+        app.update();
+        assert!(entered(&mut app).is_empty());
+        assert!(!contains(&app, zone, circle));
+
+        move_to(&mut app, circle, Vec2::ZERO);
+        app.update();
+        assert_eq!(entered(&mut app), vec![(zone, circle)]);
+        assert!(exited(&mut app).is_empty());
+        assert!(contains(&app, zone, circle));
+
+        // Staying inside sends nothing
+        app.update();
+        assert!(entered(&mut app).is_empty());
+        assert!(exited(&mut app).is_empty());
+
+        move_to(&mut app, circle, Vec2::new(500.0, 0.0));
+        app.update();
+        assert!(entered(&mut app).is_empty());
+        assert_eq!(exited(&mut app), vec![(zone, circle)]);
+        assert!(!contains(&app, zone, circle));
+    }
+
+    #[test]
+    fn despawn_inside_exits() {
+        let (mut app, zone, circle) = app();
+        move_to(&mut app, circle, Vec2::ZERO);
+        app.update();
+        assert_eq!(entered(&mut app), vec![(zone, circle)]);
+
+        app.world.despawn(circle);
+        app.update();
+        assert_eq!(exited(&mut app), vec![(zone, circle)]);
+        assert_eq!(
+            app.world
+                .get::<TriggerZone>(zone)
+                .unwrap()
+                .occupants()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn groups_filter_occupants() {
+        let (mut app, zone, circle) = app();
+        app.world
+            .entity_mut(zone)
+            .insert(CollisionGroups::new(GROUP_PLAYER, GROUP_PLAYER));
+        app.world
+            .entity_mut(circle)
+            .insert(CollisionGroups::new(GROUP_MOB, GROUP_ALL));
+        move_to(&mut app, circle, Vec2::ZERO);
+        app.update();
+
+        assert!(entered(&mut app).is_empty());
+        assert!(!contains(&app, zone, circle));
+    }
+}
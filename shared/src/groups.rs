@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+/// Group of footholds.
+pub const GROUP_FOOTHOLD: u32 = 1 << 0;
+/// Group of players.
+pub const GROUP_PLAYER: u32 = 1 << 1;
+/// Group of mobs.
+pub const GROUP_MOB: u32 = 1 << 2;
+/// Group of projectiles.
+pub const GROUP_PROJECTILE: u32 = 1 << 3;
+/// Group of items and pickups.
+pub const GROUP_ITEM: u32 = 1 << 4;
+
+/// Every group.
+pub const GROUP_ALL: u32 = u32::MAX;
+/// No group.
+pub const GROUP_NONE: u32 = 0;
+
+/// Represents the groups an entity belongs to and the groups it collides with.
+///
+/// Two entities collide only if each one is a member of a group the other one filters for.
+/// Entities without this component are in every group, except footholds,
+/// which are only in `GROUP_FOOTHOLD`.
+///
+/// # Examples
+/// ```
+/// use shared::*;
+///
+/// // Mobs collide with everything but other mobs
+/// let mob = CollisionGroups::new(GROUP_MOB, GROUP_ALL & !GROUP_MOB);
+/// assert!(!mob.interacts_with(&mob));
+///
+/// // Ghosts go through footholds
+/// let ghost = CollisionGroups::new(GROUP_MOB, GROUP_ALL & !GROUP_FOOTHOLD);
+/// assert!(!ghost.interacts_with(&CollisionGroups::foothold()));
+/// ```
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq)]
+pub struct CollisionGroups {
+    pub memberships: u32,
+    pub filter: u32,
+}
+
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self::new(GROUP_ALL, GROUP_ALL)
+    }
+}
+
+impl CollisionGroups {
+    pub fn new(memberships: u32, filter: u32) -> Self {
+        Self {
+            memberships,
+            filter,
+        }
+    }
+
+    /// Gets the groups of a foothold without a component.
+    pub fn foothold() -> Self {
+        Self::new(GROUP_FOOTHOLD, GROUP_ALL)
+    }
+
+    /// Returns true if both entities accept each other.
+    pub fn interacts_with(&self, other: &CollisionGroups) -> bool {
+        (self.memberships & other.filter) != 0 && (other.memberships & self.filter) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_interacts_with_everything() {
+        let default = CollisionGroups::default();
+        assert!(default.interacts_with(&CollisionGroups::foothold()));
+        assert!(default.interacts_with(&CollisionGroups::new(GROUP_MOB, GROUP_MOB)));
+    }
+
+    #[test]
+    fn both_sides_must_accept() {
+        let player = CollisionGroups::new(GROUP_PLAYER, GROUP_ALL);
+        let ignores_players = CollisionGroups::new(GROUP_MOB, GROUP_ALL & !GROUP_PLAYER);
+        assert!(!player.interacts_with(&ignores_players));
+        assert!(!ignores_players.interacts_with(&player));
+    }
+
+    #[test]
+    fn none_interacts_with_nothing() {
+        let none = CollisionGroups::new(GROUP_NONE, GROUP_NONE);
+        assert!(!none.interacts_with(&CollisionGroups::default()));
+    }
+}
@@ -0,0 +1,9 @@
+//! Components and plugins shared by the Bevy crates, so each crate keeps only its own values.
+
+pub mod groups;
+pub mod rigid_body;
+pub mod step;
+
+pub use groups::*;
+pub use rigid_body::*;
+pub use step::*;
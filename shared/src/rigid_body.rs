@@ -0,0 +1,320 @@
+use std::{
+    env,
+    error::Error,
+    fmt, fs, io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use ron::extensions::Extensions;
+use serde::Deserialize;
+
+use crate::step::*;
+
+/// Path of the physics config, relative to the root of the crate running the game.
+pub const PHYSICS_CONFIG_PATH: &str = "assets/physics.ron";
+
+/// Gets the path of the physics config, resolved the same way Bevy resolves assets.
+///
+/// The root is the `CARGO_MANIFEST_DIR` of the crate when running through cargo,
+/// and the directory of the executable otherwise.
+pub fn physics_config_path() -> PathBuf {
+    let root = env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            env::current_exe()
+                .ok()
+                .and_then(|path| path.parent().map(Path::to_path_buf))
+                .unwrap_or_default()
+        });
+    root.join(PHYSICS_CONFIG_PATH)
+}
+
+/// Represents the constants used for moving rigid bodies, loaded from `PHYSICS_CONFIG_PATH`.
+///
+/// Speeds are in pixels per second and accelerations in pixels per second squared.
+/// Gravity is not an acceleration: it is the rate of change of the vertical acceleration,
+/// added to it every second until `max_acceleration` is reached.
+/// Every crate starts from the same defaults, and tunes them in its own file:
+/// fields missing from the file keep their default values.
+///
+/// # Examples
+/// ```ron
+/// // assets/physics.ron
+/// (
+///     gravity: -43200.0,
+///     max_velocity_down: -20.0,
+/// )
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicsConfig {
+    /// Horizontal acceleration of a body moving on its own.
+    pub movement_speed: f32,
+    /// Vertical acceleration at the start of a jump.
+    pub jump_force: f32,
+    pub max_movement_speed: f32,
+    /// Part of the horizontal velocity pushing against the acceleration, every second.
+    pub movement_friction: f32,
+    /// Rate of change of the vertical acceleration, added to it every second.
+    pub gravity: f32,
+    pub max_acceleration: f32,
+    pub max_velocity_down: f32,
+    pub max_velocity_up: f32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            movement_speed: 5400.0,
+            jump_force: 72000.0,
+            max_movement_speed: 60.0,
+            movement_friction: -720.0,
+            gravity: -86400.0,
+            max_acceleration: 36000.0,
+            max_velocity_down: -30.0,
+            max_velocity_up: 30.0,
+        }
+    }
+}
+
+impl PhysicsConfig {
+    /// Loads a config from a RON file, with the default values for the fields it leaves out.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path of the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PhysicsConfigError> {
+        let text = fs::read_to_string(path).map_err(PhysicsConfigError::Io)?;
+        // Fields are written as plain values, not as `Some(value)`
+        let values: PhysicsOverrides = ron::Options::default()
+            .with_default_extension(Extensions::IMPLICIT_SOME)
+            .from_str(&text)
+            .map_err(|err| PhysicsConfigError::Parse(err.to_string()))?;
+        Ok(Self::default().with_overrides(Some(&values)))
+    }
+
+    /// Gets this config with the values an entity overrides.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let config = config.with_overrides(overrides);
+    /// body.acceleration.y = config.jump_force;
+    /// ```
+    pub fn with_overrides(&self, overrides: Option<&PhysicsOverrides>) -> Self {
+        let overrides = match overrides {
+            Some(overrides) => overrides,
+            None => return self.clone(),
+        };
+        Self {
+            movement_speed: overrides.movement_speed.unwrap_or(self.movement_speed),
+            jump_force: overrides.jump_force.unwrap_or(self.jump_force),
+            max_movement_speed: overrides
+                .max_movement_speed
+                .unwrap_or(self.max_movement_speed),
+            movement_friction: overrides
+                .movement_friction
+                .unwrap_or(self.movement_friction),
+            gravity: overrides.gravity.unwrap_or(self.gravity),
+            max_acceleration: overrides.max_acceleration.unwrap_or(self.max_acceleration),
+            max_velocity_down: overrides
+                .max_velocity_down
+                .unwrap_or(self.max_velocity_down),
+            max_velocity_up: overrides.max_velocity_up.unwrap_or(self.max_velocity_up),
+        }
+    }
+}
+
+/// Represents the errors raised when loading a physics config.
+#[derive(Debug)]
+pub enum PhysicsConfigError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for PhysicsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhysicsConfigError::Io(err) => write!(f, "physics config: {}", err),
+            PhysicsConfigError::Parse(err) => write!(f, "physics config: {}", err),
+        }
+    }
+}
+
+impl Error for PhysicsConfigError {}
+
+/// Replaces some values of the physics config for one entity, for heavy or light characters.
+///
+/// # Examples
+/// ```ignore
+/// // Falls faster and jumps lower than everyone else
+/// commands.entity(entity).insert(PhysicsOverrides {
+///     gravity: Some(config.gravity * 2.0),
+///     jump_force: Some(config.jump_force * 0.75),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Clone, Component, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PhysicsOverrides {
+    pub movement_speed: Option<f32>,
+    pub jump_force: Option<f32>,
+    pub max_movement_speed: Option<f32>,
+    pub movement_friction: Option<f32>,
+    pub gravity: Option<f32>,
+    pub max_acceleration: Option<f32>,
+    pub max_velocity_down: Option<f32>,
+    pub max_velocity_up: Option<f32>,
+}
+
+/// Plugin for rigid body components, whose state is held by `T`.
+///
+/// Loads the physics config from `physics_config_path`, falling back to the default
+/// values, and moves rigid bodies in the fixed physics step.
+pub struct RigidBodyPlugin<T: StepState> {
+    state: PhantomData<T>,
+}
+
+impl<T: StepState> Default for RigidBodyPlugin<T> {
+    fn default() -> Self {
+        Self { state: PhantomData }
+    }
+}
+
+impl<T: StepState> Plugin for RigidBodyPlugin<T> {
+    fn build(&self, app: &mut App) {
+        // A config inserted before the plugin wins over the file
+        if !app.world.contains_resource::<PhysicsConfig>() {
+            let config = PhysicsConfig::load(physics_config_path()).unwrap_or_else(|err| {
+                warn!("{}, using the default values", err);
+                PhysicsConfig::default()
+            });
+            app.insert_resource(config);
+        }
+
+        app.add_plugin(FixedStepPlugin::<T>::default())
+            .add_system_to_stage(
+                PhysicsStage,
+                rigid_body_system
+                    .label(RIGID_BODY_LABEL)
+                    .after(PREVIOUS_STEP_LABEL),
+            );
+    }
+}
+
+/// System used for updating rigid body components.
+///
+/// # Arguments
+///
+/// * `config`: The physics config.
+/// * `rigid_bodies`: Rigid body components, with their overrides.
+pub fn rigid_body_system(
+    config: Res<PhysicsConfig>,
+    mut rigid_bodies: Query<(&mut RigidBody, Option<&PhysicsOverrides>), With<RigidBody>>,
+) {
+    let dt = PHYSICS_TIMESTEP as f32;
+    for (mut body, overrides) in rigid_bodies.iter_mut() {
+        let config = config.with_overrides(overrides);
+
+        body.acceleration.y = (body.acceleration.y + config.gravity * dt)
+            .clamp(-config.max_acceleration, config.max_acceleration);
+        body.velocity.y = (body.velocity.y + body.acceleration.y * dt)
+            .clamp(config.max_velocity_down, config.max_velocity_up);
+
+        // Because this force is updated due to friction, we want to make sure it reaches 0.
+        body.acceleration.x += body.velocity.x * config.movement_friction * dt;
+        body.velocity.x = (body.velocity.x + body.acceleration.x * dt)
+            .clamp(-config.max_movement_speed, config.max_movement_speed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gets a config path unique to a test and process, so parallel runs don't share files.
+    fn config_path(test: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "shared_{}_{}_physics.ron",
+            test,
+            std::process::id()
+        ))
+    }
+
+    fn write_config(test: &str, text: &str) -> PathBuf {
+        let path = config_path(test);
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn without_overrides_keeps_config() {
+        let defaults = PhysicsConfig::default();
+        assert_eq!(defaults.with_overrides(None), defaults);
+        assert_eq!(
+            defaults.with_overrides(Some(&PhysicsOverrides::default())),
+            defaults
+        );
+    }
+
+    #[test]
+    fn overrides_replace_only_their_fields() {
+        let overrides = PhysicsOverrides {
+            gravity: Some(-1000.0),
+            max_velocity_up: Some(10.0),
+            ..Default::default()
+        };
+        let config = PhysicsConfig::default().with_overrides(Some(&overrides));
+
+        assert_eq!(
+            config,
+            PhysicsConfig {
+                gravity: -1000.0,
+                max_velocity_up: 10.0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn load_partial_file_keeps_defaults() {
+        let path = write_config(
+            "load_partial_file_keeps_defaults",
+            "(\n    gravity: -43200.0,\n    max_velocity_down: -20.0,\n)",
+        );
+        let config = PhysicsConfig::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            config,
+            PhysicsConfig {
+                gravity: -43200.0,
+                max_velocity_down: -20.0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn load_missing_file() {
+        let path = config_path("load_missing_file");
+        assert!(matches!(
+            PhysicsConfig::load(&path),
+            Err(PhysicsConfigError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn load_invalid_file() {
+        let path = write_config("load_invalid_file", "(gravity: \"down\")");
+        let result = PhysicsConfig::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(PhysicsConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn config_path_is_in_assets() {
+        assert!(physics_config_path().ends_with(PHYSICS_CONFIG_PATH));
+    }
+}
@@ -0,0 +1,165 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    core::{FixedTimestep, FixedTimesteps},
+    math::EulerRot,
+    prelude::*,
+    transform::TransformSystem,
+};
+
+/// Length of a physics step in seconds.
+pub const PHYSICS_TIMESTEP: f64 = 1.0 / 60.0;
+/// Label of the fixed timestep, for reading how far rendering is into the next step.
+pub const PHYSICS_TIMESTEP_LABEL: &str = "physics_timestep";
+
+/// Label of the system that stores the previous step, which runs first in every step.
+pub const PREVIOUS_STEP_LABEL: &str = "previous_step";
+/// Label of the system that integrates rigid bodies, for ordering systems that move them.
+pub const RIGID_BODY_LABEL: &str = "rigid_body";
+
+/// Stage that runs as many physics steps as the frame time covers.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
+pub struct PhysicsStage;
+
+/// Represents rigid body properties.
+#[derive(Component, Debug, Default)]
+pub struct RigidBody {
+    pub velocity: Vec2,
+    pub acceleration: Vec2,
+}
+
+/// Represents the component holding the state of a rigid body at the current step,
+/// which is stored before every step and drawn between the previous and current step.
+pub trait StepState: Component {
+    fn step_position(&self) -> Vec2;
+
+    /// Counter-clockwise rotation in radians.
+    fn step_rotation(&self) -> f32;
+}
+
+impl StepState for Transform {
+    fn step_position(&self) -> Vec2 {
+        self.translation.truncate()
+    }
+
+    fn step_rotation(&self) -> f32 {
+        self.rotation.to_euler(EulerRot::ZYX).0
+    }
+}
+
+/// Represents the state of a rigid body at the previous physics step.
+///
+/// Rendering draws the body between the previous and current step,
+/// so movement looks the same at any frame rate.
+/// Set it along with the state when teleporting, so nothing is drawn in between.
+#[derive(Clone, Component, Debug, Default)]
+pub struct PreviousStep {
+    pub position: Vec2,
+    pub rotation: f32,
+}
+
+impl PreviousStep {
+    /// Creates a previous step at a position, without rotation.
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            rotation: 0.0,
+        }
+    }
+
+    /// Gets the position and rotation between the previous step and the current state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state`: The state at the current step.
+    /// * `alpha`: How far into the next step, from 0.0 at the previous step to 1.0 at the current one.
+    pub fn interpolate(&self, state: &impl StepState, alpha: f32) -> (Vec2, f32) {
+        let rotation = state.step_rotation();
+        (
+            self.position.lerp(state.step_position(), alpha),
+            self.rotation + (rotation - self.rotation) * alpha,
+        )
+    }
+}
+
+impl<T: StepState> From<&T> for PreviousStep {
+    fn from(state: &T) -> Self {
+        Self {
+            position: state.step_position(),
+            rotation: state.step_rotation(),
+        }
+    }
+}
+
+/// Plugin for the fixed physics step of rigid bodies, whose state is held by `T`.
+///
+/// Adds the `PhysicsStage`, stores the previous step of every rigid body at the start
+/// of each step, and draws rigid bodies between their previous and current step.
+pub struct FixedStepPlugin<T: StepState> {
+    state: PhantomData<T>,
+}
+
+impl<T: StepState> Default for FixedStepPlugin<T> {
+    fn default() -> Self {
+        Self { state: PhantomData }
+    }
+}
+
+impl<T: StepState> Plugin for FixedStepPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_stage_after(
+            CoreStage::Update,
+            PhysicsStage,
+            SystemStage::parallel()
+                .with_run_criteria(
+                    FixedTimestep::step(PHYSICS_TIMESTEP).with_label(PHYSICS_TIMESTEP_LABEL),
+                )
+                .with_system(previous_step_system::<T>.label(PREVIOUS_STEP_LABEL)),
+        );
+
+        // Written after propagation, which would otherwise overwrite the global transform
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            interpolate_transform_system::<T>.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Gets how far rendering is into the next physics step, from 0.0 to 1.0.
+pub fn physics_alpha(timesteps: &FixedTimesteps) -> f32 {
+    timesteps
+        .get(PHYSICS_TIMESTEP_LABEL)
+        .map_or(1.0, |state| state.overstep_percentage() as f32)
+        .clamp(0.0, 1.0)
+}
+
+/// System used for storing the state of every rigid body before it moves.
+fn previous_step_system<T: StepState>(
+    mut rigid_bodies: Query<(&mut PreviousStep, &T), With<RigidBody>>,
+) {
+    for (mut previous, state) in rigid_bodies.iter_mut() {
+        *previous = PreviousStep::from(state);
+    }
+}
+
+/// System used for drawing rigid bodies between their previous and current physics steps.
+fn interpolate_transform_system<T: StepState>(
+    timesteps: Res<FixedTimesteps>,
+    mut rigid_bodies: Query<(&mut GlobalTransform, &T, &PreviousStep), With<RigidBody>>,
+) {
+    let alpha = physics_alpha(&timesteps);
+    for (mut global, state, previous) in rigid_bodies.iter_mut() {
+        let (position, rotation) = previous.interpolate(state, alpha);
+
+        if global.translation.x != position.x {
+            global.translation.x = position.x;
+        }
+        if global.translation.y != position.y {
+            global.translation.y = position.y;
+        }
+        let rotation = Quat::from_rotation_z(rotation);
+        if global.rotation != rotation {
+            global.rotation = rotation;
+        }
+    }
+}